@@ -28,15 +28,24 @@ use miden::{intrinsics::advice::adv_push_mapvaln, *};
 
 struct BasicWalletTxScript;
 
-// Input layout constants
-const TAG_INDEX: usize = 0;
-const AUX_INDEX: usize = 1;
-const NOTE_TYPE_INDEX: usize = 2;
-const EXECUTION_HINT_INDEX: usize = 3;
+// Input layout, version 1: a header word followed by one block per note
+//
+// header: [version, num_notes, 0, 0]
+// note:   [tag, aux, note_type, execution_hint, RECIPIENT, num_assets, 0, 0, 0, ASSET * num_assets]
+//
+// Keep in sync with the encoder in `scripts/src/tx_script.rs`.
+const INPUT_VERSION: u32 = 1;
+const WORD_SIZE: usize = 4;
+const VERSION_INDEX: usize = 0;
+const NUM_NOTES_INDEX: usize = 1;
+const TAG_OFFSET: usize = 0;
+const AUX_OFFSET: usize = 1;
+const NOTE_TYPE_OFFSET: usize = 2;
+const EXECUTION_HINT_OFFSET: usize = 3;
 const RECIPIENT_START: usize = 4;
 const RECIPIENT_END: usize = 8;
-const ASSET_START: usize = 8;
-const ASSET_END: usize = 12;
+const NUM_ASSETS_OFFSET: usize = 8;
+const NOTE_HEADER_LEN: usize = 12;
 
 impl Guest for BasicWalletTxScript {
     fn run(arg: Word) {
@@ -46,19 +55,39 @@ impl Guest for BasicWalletTxScript {
         let num_words = Felt::from_u64_unchecked(num_felts_u64 / 4);
         let commitment = arg;
         let input = adv_load_preimage(num_words, commitment);
-        let tag = input[TAG_INDEX];
-        let aux = input[AUX_INDEX];
-        let note_type = input[NOTE_TYPE_INDEX];
-        let execution_hint = input[EXECUTION_HINT_INDEX];
-        let recipient: [Felt; 4] = input[RECIPIENT_START..RECIPIENT_END].try_into().unwrap();
-        let note_idx = miden::tx::create_note(
-            tag.into(),
-            aux,
-            note_type.into(),
-            execution_hint,
-            recipient.into(),
-        );
-        let asset: [Felt; 4] = input[ASSET_START..ASSET_END].try_into().unwrap();
-        basic_wallet::move_asset_to_note(asset.into(), note_idx);
+        // Every slice below is checked against the input length, so a note or asset count
+        // larger than the input fails here instead of reading past it
+        let input_len = num_felts_u64 as usize;
+        assert!(WORD_SIZE <= input_len);
+        assert_eq(input[VERSION_INDEX], Felt::from_u32(INPUT_VERSION));
+
+        let num_notes = input[NUM_NOTES_INDEX].as_u64();
+        let mut offset = WORD_SIZE;
+        for _ in 0..num_notes {
+            assert!(offset + NOTE_HEADER_LEN <= input_len);
+            let note = &input[offset..offset + NOTE_HEADER_LEN];
+            let tag = note[TAG_OFFSET];
+            let aux = note[AUX_OFFSET];
+            let note_type = note[NOTE_TYPE_OFFSET];
+            let execution_hint = note[EXECUTION_HINT_OFFSET];
+            let recipient: [Felt; 4] = note[RECIPIENT_START..RECIPIENT_END].try_into().unwrap();
+            let num_assets = note[NUM_ASSETS_OFFSET].as_u64();
+            let note_idx = miden::tx::create_note(
+                tag.into(),
+                aux,
+                note_type.into(),
+                execution_hint,
+                recipient.into(),
+            );
+            offset += NOTE_HEADER_LEN;
+
+            for _ in 0..num_assets {
+                assert!(offset + WORD_SIZE <= input_len);
+                let asset: [Felt; 4] = input[offset..offset + WORD_SIZE].try_into().unwrap();
+                basic_wallet::move_asset_to_note(asset.into(), note_idx);
+                offset += WORD_SIZE;
+            }
+        }
+        assert_eq(Felt::from_u64_unchecked(offset as u64), num_felts);
     }
 }
@@ -0,0 +1,210 @@
+//! Authentication components for the accounts the helpers create
+//!
+//! Every account gets exactly one auth component, picked with [`AuthScheme`]. Secret keys are
+//! generated when the account is built and stored in the [`KeyStore`](crate::KeyStore), which
+//! the client also uses as its transaction authenticator, so all signing goes through it.
+
+use std::{fmt, str::FromStr};
+
+use miden_client::{
+    account::{component::RpoFalcon512, Account, StorageMap, StorageSlot},
+    auth::AuthSecretKey,
+    crypto::SecretKey,
+    Felt, Word,
+};
+use miden_lib::{account::auth::NoAuth, transaction::TransactionKernel};
+use miden_objects::{account::AccountComponent, FieldElement};
+use rand::Rng;
+
+use crate::error::DemoError;
+
+/// Auth procedure requiring an RpoFalcon512 signature from every key in the signer map
+///
+/// Every signer signs the same message, which commits to the account delta like the message of
+/// the single-key component does, so a signature cannot be replayed for a different state
+/// change.
+///
+/// Storage layout: slot 0 holds `[signer count, 0, 0, 0]`, slot 1 maps `[index, 0, 0, 0]` to
+/// the public key of each signer.
+const MULTISIG_AUTH: &str = "
+use.miden::account
+use.miden::tx
+use.std::crypto::dsa::rpo_falcon512
+
+const.CONFIG_SLOT=0
+const.PUBLIC_KEYS_SLOT=1
+
+#! Inputs:  [AUTH_ARGS, pad(12)]
+#! Outputs: [pad(16)]
+export.auth__tx_rpo_falcon512_multisig
+    dropw
+    exec.account::incr_nonce drop
+
+    # message: commitment to the account ID, the account delta (which includes the new nonce),
+    # and the input and output notes, as signed by the basic RpoFalcon512 auth component
+    exec.account::get_id push.0.0
+    # => [0, 0, account_id_prefix, account_id_suffix]
+
+    exec.account::compute_delta_commitment
+    hmerge
+    # => [h(ACCOUNT_ID, DELTA_COMMITMENT)]
+
+    exec.tx::get_input_notes_commitment
+    hmerge
+    exec.tx::get_output_notes_commitment
+    hmerge
+    # => [MSG]
+
+    push.CONFIG_SLOT exec.account::get_item
+    drop drop drop
+    # => [signers, MSG]
+
+    dup neq.0
+    while.true
+        sub.1 dup push.0.0.0
+        push.PUBLIC_KEYS_SLOT exec.account::get_map_item
+        # => [PUB_KEY, index, MSG]
+
+        movup.4 movdn.8 dupw.1 swapw
+        # => [PUB_KEY, MSG, MSG, index]
+
+        adv.push_sig.rpo_falcon512
+        exec.rpo_falcon512::verify
+        # => [MSG, index]
+
+        movup.4 dup neq.0
+    end
+    # => [0, MSG]
+
+    drop dropw
+end
+";
+
+/// Storage slot of a multisig account holding the number of signers
+const MULTISIG_CONFIG_SLOT: u8 = 0;
+
+/// Storage slot of a multisig account mapping signer indices to public keys
+const MULTISIG_PUBLIC_KEYS_SLOT: u8 = 1;
+
+/// How transactions against an account are authenticated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AuthScheme {
+    /// A single RpoFalcon512 key
+    #[default]
+    RpoFalcon512,
+    /// RpoFalcon512 keys of `signers` signers, every one of which has to sign
+    Multisig { signers: u8 },
+    /// No authentication, for test accounts; anyone can execute transactions against them
+    NoAuth,
+}
+
+impl AuthScheme {
+    /// Builds the auth component along with the secret keys it expects signatures from
+    ///
+    /// The keys are returned rather than stored so the caller only adds them to the keystore
+    /// once the account has been created.
+    pub(crate) fn build<R: Rng>(
+        &self,
+        rng: &mut R,
+    ) -> Result<(AccountComponent, Vec<AuthSecretKey>), DemoError> {
+        match *self {
+            AuthScheme::RpoFalcon512 => {
+                let key_pair = SecretKey::with_rng(rng);
+                let component = RpoFalcon512::new(key_pair.public_key()).into();
+                Ok((component, vec![AuthSecretKey::RpoFalcon512(key_pair)]))
+            }
+            AuthScheme::Multisig { signers } => {
+                let key_pairs: Vec<SecretKey> =
+                    (0..signers).map(|_| SecretKey::with_rng(rng)).collect();
+                let public_keys = key_pairs.iter().enumerate().map(|(index, key_pair)| {
+                    (
+                        signer_key(index as u8).into(),
+                        Word::from(key_pair.public_key()),
+                    )
+                });
+                let storage_slots = vec![
+                    StorageSlot::Value(Word::from([
+                        Felt::from(signers),
+                        Felt::ZERO,
+                        Felt::ZERO,
+                        Felt::ZERO,
+                    ])),
+                    StorageSlot::Map(StorageMap::with_entries(public_keys)?),
+                ];
+                let component = AccountComponent::compile(
+                    MULTISIG_AUTH,
+                    TransactionKernel::assembler(),
+                    storage_slots,
+                )?
+                .with_supports_all_types();
+                let keys = key_pairs
+                    .into_iter()
+                    .map(AuthSecretKey::RpoFalcon512)
+                    .collect();
+                Ok((component, keys))
+            }
+            AuthScheme::NoAuth => Ok((NoAuth.into(), vec![])),
+        }
+    }
+
+    /// Returns the public keys `account`'s auth component checks signatures against
+    ///
+    /// The auth component is always the first component, so its slots start right after the
+    /// slot the kernel reserves in faucets.
+    pub fn public_keys(&self, account: &Account) -> Result<Vec<Word>, DemoError> {
+        let storage = account.storage();
+        let offset = u8::from(account.is_faucet());
+        match *self {
+            AuthScheme::RpoFalcon512 => Ok(vec![storage.get_item(offset)?]),
+            AuthScheme::Multisig { .. } => {
+                let signers = storage.get_item(offset + MULTISIG_CONFIG_SLOT)?[0].as_int() as u8;
+                (0..signers)
+                    .map(|index| {
+                        Ok(storage
+                            .get_map_item(offset + MULTISIG_PUBLIC_KEYS_SLOT, signer_key(index))?)
+                    })
+                    .collect()
+            }
+            AuthScheme::NoAuth => Ok(vec![]),
+        }
+    }
+}
+
+/// Key of signer `index` in the multisig public key map
+fn signer_key(index: u8) -> Word {
+    Word::from([Felt::from(index), Felt::ZERO, Felt::ZERO, Felt::ZERO])
+}
+
+impl fmt::Display for AuthScheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuthScheme::RpoFalcon512 => f.write_str("rpo-falcon512"),
+            AuthScheme::Multisig { signers } => write!(f, "multisig:{signers}"),
+            AuthScheme::NoAuth => f.write_str("none"),
+        }
+    }
+}
+
+impl FromStr for AuthScheme {
+    type Err = String;
+
+    /// Parses `rpo-falcon512`, `none` or `multisig:<signers>`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "rpo-falcon512" => Ok(AuthScheme::RpoFalcon512),
+            "none" => Ok(AuthScheme::NoAuth),
+            _ => {
+                let signers = s.strip_prefix("multisig:").ok_or_else(|| {
+                    format!(
+                        "unknown auth scheme '{s}', expected rpo-falcon512, none or \
+                         multisig:<signers>"
+                    )
+                })?;
+                match signers.parse::<u8>() {
+                    Ok(signers) if signers > 0 => Ok(AuthScheme::Multisig { signers }),
+                    _ => Err(format!("invalid multisig signer count '{signers}'")),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,248 @@
+//! Counter contract test module
+
+use clap::Parser;
+use miden_client::{
+    transaction::{OutputNote, TransactionRequestBuilder},
+    Felt, Word,
+};
+use miden_demo::{
+    compile_rust_packages,
+    counter::{assert_counter_storage, counter_key, counter_value, COUNTER_STORAGE_MAP},
+    create_account_with_component, create_note_from_package,
+    deployments::DEFAULT_DEPLOYMENTS_PATH,
+    network::{build_client, print_tx_link},
+    package::package_path,
+    AccountCreationConfig, AccountRequirements, AccountTypeArg, AuthScheme, ClientSettings,
+    CompileOptions, DemoError, Deployments, NoteCreationConfig, PackageKind, StorageInit,
+    StorageModeArg,
+};
+use miden_objects::FieldElement;
+use std::path::PathBuf;
+
+/// Command-line arguments
+#[derive(Parser)]
+struct Args {
+    #[command(flatten)]
+    client: ClientSettings,
+
+    /// Load prebuilt `<crate>.masp` packages from this directory instead of compiling
+    #[arg(long)]
+    package_dir: Option<PathBuf>,
+
+    /// Create a new counter account even if one from an earlier run can be reused
+    #[arg(long)]
+    fresh: bool,
+
+    /// Type of the counter account
+    #[arg(long, value_enum, default_value_t)]
+    account_type: AccountTypeArg,
+
+    /// Storage mode of the counter account
+    #[arg(long, value_enum, default_value_t)]
+    storage_mode: StorageModeArg,
+
+    /// Auth scheme of the counter account: `rpo-falcon512`, `none` or `multisig:<signers>`
+    #[arg(long, default_value_t)]
+    auth: AuthScheme,
+}
+
+/// Tests the counter contract deployment and note consumption workflow.
+#[tokio::main]
+async fn main() -> Result<(), DemoError> {
+    println!("=== Miden Counter Contract Deployment and Note Consumption ===");
+    println!("This script demonstrates the full workflow of:");
+    println!("1. Compiling Rust packages to Miden");
+    println!("2. Creating accounts with counter contract components");
+    println!("3. Creating and consuming counter notes");
+    println!("4. Verifying counter incrementation");
+    println!();
+
+    let args = Args::parse();
+    let network = args.client.network;
+    let mut deployments = Deployments::load(DEFAULT_DEPLOYMENTS_PATH)?;
+
+    // Initialize client & keystore
+    let (mut client, keystore) = build_client(&args.client).await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("✓ Connected to Miden {network}");
+    println!("  Latest block: {}", sync_summary.block_num);
+
+    // Compile the contracts first (before creating any runtime)
+    println!("\n[STEP 1] Compiling Rust packages...");
+    let packages = compile_rust_packages(
+        &[
+            package_path(args.package_dir.as_deref(), "counter-contract"),
+            package_path(args.package_dir.as_deref(), "counter-contract-note"),
+        ],
+        &CompileOptions::default(),
+    )?;
+    let contract_package = packages[0].package_of_kind(PackageKind::Account)?;
+    let note_package = packages[1].package_of_kind(PackageKind::NoteScript)?;
+    println!("✓ Compiled counter contract package");
+    println!("✓ Compiled counter note package");
+
+    // Create the counter account with initial storage
+    let counter_account = match deployments
+        .reusable_account(
+            &mut client,
+            keystore.as_ref(),
+            network,
+            "counter",
+            &AccountRequirements {
+                packages: &[contract_package.as_ref()],
+                auth: args.auth,
+                account_type: args.account_type.into(),
+                storage_mode: args.storage_mode.into(),
+            },
+            args.fresh,
+        )
+        .await?
+    {
+        Some(account) => {
+            println!("\n[STEP 2] Reusing counter account from an earlier run...");
+            account
+        }
+        None => {
+            println!("\n[STEP 2] Creating counter account with initial storage...");
+            let value = Word::from([Felt::ZERO, Felt::ZERO, Felt::ZERO, Felt::ONE]);
+            let config = AccountCreationConfig {
+                storage_init: Some(StorageInit::default().with_map_entry(
+                    COUNTER_STORAGE_MAP,
+                    counter_key(),
+                    value,
+                )),
+                account_type: args.account_type.into(),
+                storage_mode: args.storage_mode.into(),
+                auth: args.auth,
+                ..Default::default()
+            };
+
+            let account = create_account_with_component(
+                &mut client,
+                keystore.clone(),
+                contract_package.clone(),
+                config,
+            )
+            .await?;
+            deployments.record_account(
+                network,
+                "counter",
+                &account,
+                &[contract_package.as_ref()],
+                &args.auth,
+            );
+            deployments.save()?;
+            println!("✓ Counter account created successfully!");
+            account
+        }
+    };
+    println!(
+        "  Account ID: {}",
+        counter_account.id().to_bech32(network.network_id())
+    );
+
+    // A new counter starts at 1; a reused one continues from its last value
+    let initial_value = counter_value(
+        client
+            .get_account(counter_account.id())
+            .await?
+            .ok_or(DemoError::AccountNotFound(counter_account.id()))?
+            .account(),
+        &contract_package,
+    )?;
+    println!("✓ Initial counter value: {initial_value}");
+
+    // Create the counter note from sender to counter
+    println!("\n[STEP 3] Creating counter note...");
+    let counter_note = create_note_from_package(
+        &mut client,
+        note_package.clone(),
+        counter_account.id(),
+        NoteCreationConfig::default(),
+    )?;
+    deployments.record_note(
+        network,
+        "counter-note",
+        &counter_note,
+        Some(note_package.as_ref()),
+    );
+    println!("✓ Counter note created");
+    println!("  Note hash: {:?}", counter_note.id().to_hex());
+
+    // Submit transaction to create the note
+    println!("\n[STEP 4] Submitting transaction to create the note...");
+    let note_request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(counter_note.clone())])
+        .build()?;
+
+    let tx_result = client
+        .new_transaction(counter_account.id(), note_request)
+        .await?;
+    let executed_transaction = tx_result.executed_transaction();
+
+    assert_eq!(executed_transaction.output_notes().num_notes(), 1);
+
+    let executed_tx_output_note = executed_transaction.output_notes().get_note(0);
+    assert_eq!(executed_tx_output_note.id(), counter_note.id());
+    let create_note_tx_id = executed_transaction.id();
+    client.submit_transaction(tx_result).await?;
+    deployments.record_account_transaction(network, counter_account.id(), create_note_tx_id);
+    deployments.record_note_transaction(network, counter_note.id(), create_note_tx_id);
+    deployments.save()?;
+    println!("✓ Counter note creation transaction submitted");
+    print_tx_link(network, create_note_tx_id);
+
+    // Consume the note to increment the counter
+    println!("\n[STEP 5] Consuming the note to increment the counter...");
+    let counter_note_id = counter_note.id();
+    let consume_request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(counter_note, None)])
+        .build()?;
+
+    let tx_result = client
+        .new_transaction(counter_account.id(), consume_request)
+        .await?;
+    let consume_tx_id = tx_result.executed_transaction().id();
+    println!("✓ Counter note consumption transaction created");
+    print_tx_link(network, consume_tx_id);
+
+    client.submit_transaction(tx_result).await?;
+    deployments.record_account_transaction(network, counter_account.id(), consume_tx_id);
+    deployments.record_note_transaction(network, counter_note_id, consume_tx_id);
+    deployments.save()?;
+    println!("✓ Counter note consumption transaction submitted");
+
+    // Sync state to get latest updates
+    println!("\n[STEP 6] Syncing state and verifying counter incrementation...");
+    let sync_result = client.sync_state().await?;
+    println!("✓ Synced to block: {}", sync_result.block_num);
+
+    // The counter contract storage value should be incremented after the note is consumed
+    let expected_value = initial_value + 1;
+    assert_counter_storage(
+        client
+            .get_account(counter_account.id())
+            .await?
+            .ok_or(DemoError::AccountNotFound(counter_account.id()))?
+            .account(),
+        &contract_package,
+        expected_value,
+    );
+    println!("✓ Counter value after incrementation verified: {expected_value}");
+
+    // Final summary
+    println!("\n=== SUCCESS: Counter Contract Workflow Completed! ===");
+    println!();
+    println!("✓ Compiled Rust packages to Miden");
+    println!("✓ Created or reused the counter account");
+    println!("✓ Created and submitted counter note");
+    println!("✓ Consumed counter note to increment counter");
+    println!("✓ Verified counter incrementation (value: {initial_value} → {expected_value})");
+    println!("✓ Recorded deployments in {DEFAULT_DEPLOYMENTS_PATH}");
+    println!();
+    println!("The complete counter contract deployment and note consumption");
+    println!("workflow has been successfully demonstrated using the new Rust compiler!");
+
+    Ok(())
+}
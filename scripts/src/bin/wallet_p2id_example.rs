@@ -0,0 +1,396 @@
+//! Basic wallet test module
+
+use clap::Parser;
+use miden_client::{
+    account::{AccountStorageMode, AccountType},
+    asset::{FungibleAsset, TokenSymbol},
+    transaction::TransactionRequestBuilder,
+    Felt,
+};
+use miden_demo::{
+    compile_rust_packages, create_account_with_component,
+    deployments::DEFAULT_DEPLOYMENTS_PATH,
+    faucet::{create_fungible_faucet_account, is_fungible_faucet_for, mint_to_recipients},
+    network::{build_client, print_tx_link},
+    package::package_path,
+    wallet::{assert_account_has_fungible_asset, fungible_balance, send_asset_to_account},
+    AccountCreationConfig, AccountRequirements, AuthScheme, ClientSettings, CompileOptions,
+    DemoError, Deployments, PackageKind,
+};
+use std::path::PathBuf;
+
+/// Command-line arguments
+#[derive(Parser)]
+struct Args {
+    #[command(flatten)]
+    client: ClientSettings,
+
+    /// Load prebuilt `<crate>.masp` packages from this directory instead of compiling
+    #[arg(long)]
+    package_dir: Option<PathBuf>,
+
+    /// Create new faucet and wallet accounts even if ones from an earlier run can be reused
+    #[arg(long)]
+    fresh: bool,
+
+    /// Auth scheme of the faucet and wallet accounts: `rpo-falcon512`, `none` or
+    /// `multisig:<signers>`
+    #[arg(long, default_value_t)]
+    auth: AuthScheme,
+}
+
+/// Tests the basic-wallet contract deployment and p2id note consumption workflow.
+#[tokio::main]
+async fn main() -> Result<(), DemoError> {
+    println!("=== Miden Basic Wallet P2ID Example ===");
+    println!("This script demonstrates the full workflow of:");
+    println!("1. Compiling basic wallet, p2id note, and transaction script packages");
+    println!("2. Creating fungible faucet and wallet accounts");
+    println!("3. Minting tokens to Alice's wallet");
+    println!("4. Transferring tokens from Alice to Bob using p2id notes");
+    println!("5. Verifying asset transfers");
+    println!();
+
+    let args = Args::parse();
+    let network = args.client.network;
+    let mut deployments = Deployments::load(DEFAULT_DEPLOYMENTS_PATH)?;
+
+    // Initialize client & keystore
+    let (mut client, keystore) = build_client(&args.client).await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("✓ Connected to Miden {network}");
+    println!("  Latest block: {}", sync_summary.block_num);
+
+    // Compile the contracts first (before creating any runtime)
+    println!("\n[STEP 1] Compiling Rust packages...");
+    let packages = compile_rust_packages(
+        &[
+            package_path(args.package_dir.as_deref(), "basic-wallet"),
+            package_path(args.package_dir.as_deref(), "p2id-note"),
+            package_path(args.package_dir.as_deref(), "basic-wallet-tx-script"),
+        ],
+        &CompileOptions::default(),
+    )?;
+    let wallet_package = packages[0].package_of_kind(PackageKind::Account)?;
+    let note_package = packages[1].package_of_kind(PackageKind::NoteScript)?;
+    let tx_script_package = packages[2].package_of_kind(PackageKind::TransactionScript)?;
+    println!("✓ Compiled basic wallet package");
+    println!("✓ Compiled p2id note package");
+    println!("✓ Compiled basic wallet transaction script package");
+
+    // Create a fungible faucet account
+    let token_symbol = TokenSymbol::new("TEST")?;
+    let decimals = 8u8;
+    let max_supply = Felt::new(1_000_000_000); // 1 billion tokens
+    let faucet_account = match deployments
+        .reusable_account(
+            &mut client,
+            keystore.as_ref(),
+            network,
+            "faucet",
+            &AccountRequirements {
+                packages: &[],
+                auth: args.auth,
+                account_type: AccountType::FungibleFaucet,
+                storage_mode: AccountStorageMode::Public,
+            },
+            args.fresh,
+        )
+        .await?
+        .filter(|account| is_fungible_faucet_for(account, token_symbol, decimals, max_supply))
+    {
+        Some(account) => {
+            println!("\n[STEP 2] Reusing fungible faucet account from an earlier run...");
+            account
+        }
+        None => {
+            println!("\n[STEP 2] Creating fungible faucet account...");
+
+            let account = create_fungible_faucet_account(
+                &mut client,
+                keystore.clone(),
+                token_symbol,
+                decimals,
+                max_supply,
+                args.auth,
+            )
+            .await?;
+            deployments.record_account(network, "faucet", &account, &[], &args.auth);
+            deployments.save()?;
+            println!("✓ Faucet account created successfully!");
+            account
+        }
+    };
+    println!(
+        "  Faucet ID: {}",
+        faucet_account.id().to_bech32(network.network_id())
+    );
+
+    // Create Alice's account with basic-wallet component
+    let alice_account = match deployments
+        .reusable_account(
+            &mut client,
+            keystore.as_ref(),
+            network,
+            "alice",
+            &AccountRequirements {
+                packages: &[wallet_package.as_ref()],
+                auth: args.auth,
+                account_type: AccountType::RegularAccountUpdatableCode,
+                storage_mode: AccountStorageMode::Public,
+            },
+            args.fresh,
+        )
+        .await?
+    {
+        Some(account) => {
+            println!("\n[STEP 3] Reusing Alice's wallet account from an earlier run...");
+            account
+        }
+        None => {
+            println!("\n[STEP 3] Creating Alice's wallet account...");
+            let alice_config = AccountCreationConfig {
+                with_basic_wallet: false,
+                auth: args.auth,
+                ..Default::default()
+            };
+            let account = create_account_with_component(
+                &mut client,
+                keystore.clone(),
+                wallet_package.clone(),
+                alice_config,
+            )
+            .await?;
+            deployments.record_account(
+                network,
+                "alice",
+                &account,
+                &[wallet_package.as_ref()],
+                &args.auth,
+            );
+            deployments.save()?;
+            println!("✓ Alice's account created successfully!");
+            account
+        }
+    };
+    println!(
+        "  Alice ID: {}",
+        alice_account.id().to_bech32(network.network_id())
+    );
+    let alice_initial_balance =
+        fungible_balance(&mut client, alice_account.id(), faucet_account.id()).await?;
+
+    println!("\n[STEP 4] Minting tokens from faucet to Alice...");
+
+    let mint_amount = 100_000u64; // 100,000 tokens
+
+    // Mint to Alice through a p2id note
+    let (mint_tx_id, mint_notes) = mint_to_recipients(
+        &mut client,
+        faucet_account.id(),
+        note_package.clone(),
+        &[(alice_account.id(), mint_amount)],
+    )
+    .await?;
+    // One note per recipient, so Alice's is the only one
+    for note in &mint_notes {
+        deployments.record_note(network, "mint-note", note, Some(note_package.as_ref()));
+        deployments.record_note_transaction(network, note.id(), mint_tx_id);
+        println!("✓ P2ID mint note created");
+        println!("  Note hash: {:?}", note.id().to_hex());
+    }
+    print_tx_link(network, mint_tx_id);
+    deployments.record_account_transaction(network, faucet_account.id(), mint_tx_id);
+    deployments.save()?;
+    println!("✓ Mint transaction submitted");
+
+    println!("\n[STEP 5] Alice consuming mint note...");
+
+    let mint_note_ids: Vec<_> = mint_notes.iter().map(|note| note.id()).collect();
+    let consume_request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes(mint_notes.into_iter().map(|note| (note, None)))
+        .build()?;
+
+    let consume_tx = client
+        .new_transaction(alice_account.id(), consume_request)
+        .await?;
+
+    let alice_consume_tx_id = consume_tx.executed_transaction().id();
+    client.submit_transaction(consume_tx).await?;
+    deployments.record_account_transaction(network, alice_account.id(), alice_consume_tx_id);
+    for note_id in mint_note_ids {
+        deployments.record_note_transaction(network, note_id, alice_consume_tx_id);
+    }
+    deployments.save()?;
+    println!("✓ Alice consumed mint note");
+    print_tx_link(network, alice_consume_tx_id);
+
+    // Sync state to get latest updates
+    println!("\n[STEP 6] Syncing state and verifying Alice's balance...");
+    let sync_result = client.sync_state().await?;
+    println!("✓ Synced to block: {}", sync_result.block_num);
+
+    assert_account_has_fungible_asset(
+        &mut client,
+        alice_account.id(),
+        faucet_account.id(),
+        alice_initial_balance + mint_amount,
+    )
+    .await;
+    println!(
+        "✓ Alice's account has the minted asset: {} tokens",
+        alice_initial_balance + mint_amount
+    );
+
+    let bob_account = match deployments
+        .reusable_account(
+            &mut client,
+            keystore.as_ref(),
+            network,
+            "bob",
+            &AccountRequirements {
+                packages: &[wallet_package.as_ref()],
+                auth: args.auth,
+                account_type: AccountType::RegularAccountUpdatableCode,
+                storage_mode: AccountStorageMode::Public,
+            },
+            args.fresh,
+        )
+        .await?
+    {
+        Some(account) => {
+            println!("\n[STEP 7] Reusing Bob's wallet account from an earlier run...");
+            account
+        }
+        None => {
+            println!("\n[STEP 7] Creating Bob's wallet account...");
+            let bob_config = AccountCreationConfig {
+                with_basic_wallet: false,
+                auth: args.auth,
+                ..Default::default()
+            };
+            let account = create_account_with_component(
+                &mut client,
+                keystore.clone(),
+                wallet_package.clone(),
+                bob_config,
+            )
+            .await?;
+            deployments.record_account(
+                network,
+                "bob",
+                &account,
+                &[wallet_package.as_ref()],
+                &args.auth,
+            );
+            deployments.save()?;
+            println!("✓ Bob's account created successfully!");
+            account
+        }
+    };
+    println!(
+        "  Bob ID: {}",
+        bob_account.id().to_bech32(network.network_id())
+    );
+    let bob_initial_balance =
+        fungible_balance(&mut client, bob_account.id(), faucet_account.id()).await?;
+
+    println!("\n[STEP 8] Alice creating p2id note for Bob...");
+
+    let transfer_amount = 10_000u64; // 10,000 tokens
+    let transfer_asset = FungibleAsset::new(faucet_account.id(), transfer_amount)?;
+
+    // Use the send_asset_to_account helper function like in the original test
+    let (alice_tx_id, bob_note) = send_asset_to_account(
+        &mut client,
+        alice_account.id(),
+        bob_account.id(),
+        transfer_asset,
+        note_package.clone(),
+        tx_script_package,
+        None, // Use default configuration
+    )
+    .await?;
+
+    deployments.record_note(
+        network,
+        "transfer-note",
+        &bob_note,
+        Some(note_package.as_ref()),
+    );
+    deployments.record_account_transaction(network, alice_account.id(), alice_tx_id);
+    deployments.record_note_transaction(network, bob_note.id(), alice_tx_id);
+    deployments.save()?;
+    println!("✓ Alice created p2id note for Bob");
+    print_tx_link(network, alice_tx_id);
+
+    println!("\n[STEP 9] Bob consuming p2id note...");
+
+    let bob_note_id = bob_note.id();
+    let bob_consume_request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(bob_note, None)])
+        .build()?;
+
+    let bob_consume_tx = client
+        .new_transaction(bob_account.id(), bob_consume_request)
+        .await?;
+    let bob_consume_tx_id = bob_consume_tx.executed_transaction().id();
+    println!("✓ Bob consume transaction created");
+    print_tx_link(network, bob_consume_tx_id);
+
+    client.submit_transaction(bob_consume_tx).await?;
+    deployments.record_account_transaction(network, bob_account.id(), bob_consume_tx_id);
+    deployments.record_note_transaction(network, bob_note_id, bob_consume_tx_id);
+    deployments.save()?;
+    println!("✓ Bob consumed p2id note");
+
+    println!("\n[STEP 10] Final verification...");
+    let sync_result = client.sync_state().await?;
+    println!("✓ Synced to block: {}", sync_result.block_num);
+
+    let alice_final_balance = alice_initial_balance + mint_amount - transfer_amount;
+    let bob_final_balance = bob_initial_balance + transfer_amount;
+
+    assert_account_has_fungible_asset(
+        &mut client,
+        bob_account.id(),
+        faucet_account.id(),
+        bob_final_balance,
+    )
+    .await;
+    println!(
+        "✓ Bob's account has the transferred asset: {} tokens",
+        bob_final_balance
+    );
+
+    assert_account_has_fungible_asset(
+        &mut client,
+        alice_account.id(),
+        faucet_account.id(),
+        alice_final_balance,
+    )
+    .await;
+    println!(
+        "✓ Alice's account reflects the new balance: {} tokens",
+        alice_final_balance
+    );
+
+    // Final summary
+    println!("\n=== SUCCESS: Basic Wallet P2ID Workflow Completed! ===");
+    println!();
+    println!("✓ Compiled basic wallet, p2id note, and transaction script packages");
+    println!("✓ Created or reused the fungible faucet account");
+    println!("✓ Created or reused Alice's and Bob's wallet accounts");
+    println!("✓ Minted {} tokens to Alice", mint_amount);
+    println!("✓ Transferred {} tokens from Alice to Bob", transfer_amount);
+    println!("✓ Verified final balances:");
+    println!("  - Alice: {} tokens", alice_final_balance);
+    println!("  - Bob: {} tokens", bob_final_balance);
+    println!("✓ Recorded deployments in {DEFAULT_DEPLOYMENTS_PATH}");
+    println!();
+    println!("The complete basic wallet P2ID workflow has been successfully");
+    println!("demonstrated using the Rust compiler and Miden client!");
+
+    Ok(())
+}
@@ -0,0 +1,240 @@
+//! On-disk cache of compiled packages, keyed by a hash of their sources
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::{Arc, OnceLock},
+};
+
+use miden_mast_package::Package;
+use tempfile::NamedTempFile;
+
+use crate::{
+    compiler::CompileOptions,
+    error::DemoError,
+    manifest::MidenManifest,
+    package::{load_package, save_package, PACKAGE_EXTENSION},
+};
+
+/// Crates of the compiler toolchain the scripts link against; their resolved versions are part
+/// of every cache key
+const COMPILER_CRATES: [&str; 6] = [
+    "cargo-miden",
+    "midenc-codegen-masm",
+    "midenc-compile",
+    "midenc-frontend-wasm",
+    "midenc-hir",
+    "midenc-session",
+];
+
+/// Environment variable overriding the cache directory
+const CACHE_DIR_ENV: &str = "MIDEN_DEMO_CACHE_DIR";
+
+/// File extensions that are hashed when computing a cache key
+const HASHED_EXTENSIONS: [&str; 3] = ["rs", "toml", "wit"];
+
+/// A content-addressed store of serialized packages
+pub struct PackageCache {
+    dir: PathBuf,
+}
+
+impl Default for PackageCache {
+    /// Uses `$MIDEN_DEMO_CACHE_DIR`, or `target/miden-package-cache` in the working directory
+    fn default() -> Self {
+        let dir = std::env::var_os(CACHE_DIR_ENV)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("target").join("miden-package-cache"));
+        Self::new(dir)
+    }
+}
+
+impl PackageCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Computes the cache key for the crate at `package_path`
+    ///
+    /// The key covers every `.rs`, `.toml` and `.wit` file of the crate and of the local crates
+    /// and WIT files it depends on, the crate's `Cargo.lock`, the compile options and the
+    /// [`compiler_version`]. Files are identified by their path relative to the crate, so the
+    /// key does not depend on where the checkout lives.
+    pub fn key(
+        &self,
+        package_path: impl AsRef<Path>,
+        options: &CompileOptions,
+    ) -> Result<String, DemoError> {
+        let package_path = package_path.as_ref();
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(compiler_version()?.as_bytes());
+        hasher.update(format!("{options:?}").as_bytes());
+
+        // The lockfile pins the revision of git dependencies such as the Miden SDK
+        let lockfile = package_path.join("Cargo.lock");
+        if lockfile.is_file() {
+            hasher.update(b"Cargo.lock");
+            hasher.update(&fs::read(&lockfile)?);
+        }
+
+        let mut roots = vec![package_path.to_path_buf()];
+        roots.extend(local_dependency_paths(package_path)?);
+        roots.sort();
+        roots.dedup();
+
+        for root in &roots {
+            let mut files = vec![];
+            collect_source_files(root, &mut files)?;
+            files.sort();
+
+            hasher.update(relative_path(root, package_path).as_bytes());
+            for file in files {
+                hasher.update(relative_path(&file, root).as_bytes());
+                hasher.update(&fs::read(&file)?);
+            }
+        }
+
+        Ok(hasher.finalize().to_hex().to_string())
+    }
+
+    /// Returns the cached package for `key`, if there is a readable one
+    pub fn load(&self, key: &str) -> Option<Arc<Package>> {
+        let path = self.entry_path(key);
+        if !path.exists() {
+            return None;
+        }
+        match load_package(&path) {
+            Ok(package) => Some(package),
+            Err(_) => {
+                // A corrupt entry is treated as a miss and rebuilt
+                let _ = fs::remove_file(&path);
+                None
+            }
+        }
+    }
+
+    /// Stores `package` under `key`
+    pub fn store(&self, key: &str, package: &Package) -> Result<(), DemoError> {
+        // Write to a uniquely named temporary file first, so readers never observe a partial
+        // entry and concurrent compiles of the same crate do not clobber each other
+        fs::create_dir_all(&self.dir)?;
+        let tmp = NamedTempFile::new_in(&self.dir)?;
+        save_package(tmp.path(), package)?;
+        tmp.persist(self.entry_path(key))
+            .map_err(|err| DemoError::Io(err.error))?;
+        Ok(())
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.{PACKAGE_EXTENSION}"))
+    }
+}
+
+/// Returns the version of the compiler toolchain packages are built with
+///
+/// This is read from the resolved [`COMPILER_CRATES`] in the scripts' own `Cargo.lock`, since
+/// the compiler is linked in. Without a lockfile, e.g. when this crate is used as a dependency,
+/// it falls back to the output of `cargo miden --version`.
+pub fn compiler_version() -> Result<String, DemoError> {
+    static VERSION: OnceLock<Option<String>> = OnceLock::new();
+    VERSION
+        .get_or_init(|| {
+            let lockfile = Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.lock");
+            locked_versions(&lockfile, &COMPILER_CRATES).or_else(cargo_miden_version)
+        })
+        .clone()
+        .ok_or_else(|| DemoError::Compilation {
+            package: "cargo-miden".to_string(),
+            message: "cannot determine the compiler version: no Cargo.lock and \
+                      `cargo miden --version` failed"
+                .to_string(),
+        })
+}
+
+/// Returns `name version source` of every package in `lockfile` named in `crates`
+fn locked_versions(lockfile: &Path, crates: &[&str]) -> Option<String> {
+    let lock: toml::Table = toml::from_str(&fs::read_to_string(lockfile).ok()?).ok()?;
+    let versions: Vec<String> = lock
+        .get("package")?
+        .as_array()?
+        .iter()
+        .filter(|package| {
+            package
+                .get("name")
+                .and_then(|name| name.as_str())
+                .is_some_and(|name| crates.contains(&name))
+        })
+        .map(|package| {
+            ["name", "version", "source"]
+                .map(|field| {
+                    package
+                        .get(field)
+                        .and_then(|value| value.as_str())
+                        .unwrap_or_default()
+                })
+                .join(" ")
+        })
+        .collect();
+    (!versions.is_empty()).then(|| versions.join("\n"))
+}
+
+fn cargo_miden_version() -> Option<String> {
+    let output = Command::new("cargo")
+        .args(["miden", "--version"])
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Renders `path` relative to `base`, with `/` separators, or as-is if it is not below `base`
+fn relative_path(path: &Path, base: &Path) -> String {
+    let relative = path.strip_prefix(base).unwrap_or(path);
+    relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Returns the local crates and WIT files the crate at `package_path` depends on
+fn local_dependency_paths(package_path: &Path) -> Result<Vec<PathBuf>, DemoError> {
+    let manifest = MidenManifest::read(package_path)?;
+    let paths = manifest
+        .dependencies
+        .into_iter()
+        .chain(manifest.wit_dependencies)
+        // WIT deps inside the crate itself are already covered
+        .filter(|path| !path.starts_with(package_path.join("wit-deps")))
+        .collect();
+    Ok(paths)
+}
+
+/// Recursively collects the hashed source files under `path`, skipping build output
+fn collect_source_files(path: &Path, files: &mut Vec<PathBuf>) -> Result<(), DemoError> {
+    if path.is_file() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(path)? {
+        let path = entry?.path();
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned());
+        if path.is_dir() {
+            if matches!(file_name.as_deref(), Some("target") | Some(".git")) {
+                continue;
+            }
+            collect_source_files(&path, files)?;
+        } else if path
+            .extension()
+            .is_some_and(|ext| HASHED_EXTENSIONS.iter().any(|hashed| ext == *hashed))
+        {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,760 @@
+//! Compilation of Rust contract crates into Miden packages
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use miden_assembly::LibraryPath;
+use miden_mast_package::Package;
+
+use crate::{
+    cache::PackageCache,
+    error::DemoError,
+    manifest::MidenManifest,
+    package::{
+        check_package_kind, load_or_compile_package, save_package, PackageKind, PACKAGE_EXTENSION,
+    },
+};
+
+/// Debug info level passed to midenc as `--debug`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum DebugInfo {
+    None,
+    Line,
+    Full,
+}
+
+/// Optimization level passed to midenc as `--opt-level`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OptLevel {
+    #[value(name = "0")]
+    None,
+    #[value(name = "1")]
+    Basic,
+    #[value(name = "2")]
+    Balanced,
+    #[value(name = "3")]
+    Max,
+    #[value(name = "s")]
+    Size,
+    #[value(name = "z")]
+    SizeMin,
+}
+
+/// Options controlling how a Rust crate is compiled to a Miden package
+#[derive(Debug, Clone)]
+pub struct CompileOptions {
+    /// Build the crate with `--release`
+    pub release: bool,
+    /// Flags passed to midenc
+    pub midenc_flags: Vec<String>,
+    /// `RUSTFLAGS` for the Rust to Wasm build
+    pub rustflags: Vec<String>,
+    /// Debug info level; the midenc default is used if unset
+    pub debug_info: Option<DebugInfo>,
+    /// Optimization level; the midenc default is used if unset
+    pub opt_level: Option<OptLevel>,
+    /// Hand-written MASM modules linked into the package, as (module path, source)
+    pub masm_modules: Vec<(LibraryPath, String)>,
+    /// Always compile, neither loading from nor storing to the package cache
+    pub no_cache: bool,
+}
+
+impl Default for CompileOptions {
+    fn default() -> Self {
+        Self {
+            release: true,
+            midenc_flags: vec!["--verbose".to_string()],
+            rustflags: vec![
+                "-C".to_string(),
+                "target-feature=+bulk-memory".to_string(),
+                "--remap-path-prefix".to_string(),
+                "../../=../../".to_string(),
+            ],
+            debug_info: None,
+            opt_level: None,
+            masm_modules: vec![],
+            no_cache: false,
+        }
+    }
+}
+
+impl CompileOptions {
+    /// Reads a `.masm` file and links it into the package as module `path`
+    pub fn link_masm_file(
+        &mut self,
+        path: LibraryPath,
+        file: impl AsRef<Path>,
+    ) -> Result<&mut Self, DemoError> {
+        let source = std::fs::read_to_string(file)?;
+        self.masm_modules.push((path, source));
+        Ok(self)
+    }
+
+    /// Returns the full list of midenc flags, including the debug info and optimization levels
+    pub fn all_midenc_flags(&self) -> Vec<String> {
+        let mut flags = self.midenc_flags.clone();
+        if let Some(debug_info) = self.debug_info {
+            let level = match debug_info {
+                DebugInfo::None => "none",
+                DebugInfo::Line => "line",
+                DebugInfo::Full => "full",
+            };
+            flags.push(format!("--debug={level}"));
+        }
+        if let Some(opt_level) = self.opt_level {
+            let level = match opt_level {
+                OptLevel::None => "0",
+                OptLevel::Basic => "1",
+                OptLevel::Balanced => "2",
+                OptLevel::Max => "3",
+                OptLevel::Size => "s",
+                OptLevel::SizeMin => "z",
+            };
+            flags.push(format!("--opt-level={level}"));
+        }
+        flags
+    }
+}
+
+/// Helper to compile a Rust package to Miden using the real compiler
+///
+/// Packages whose sources are unchanged since the last build are loaded from the default
+/// [`PackageCache`] instead of being recompiled, unless `options.no_cache` is set.
+pub fn compile_rust_package(
+    package_path: &str,
+    options: &CompileOptions,
+) -> Result<Arc<Package>, DemoError> {
+    if options.no_cache {
+        return compile_rust_package_uncached(package_path, options);
+    }
+    let cache = PackageCache::default();
+    let key = cache.key(package_path, options)?;
+    if let Some(package) = cache.load(&key) {
+        println!("  ✓ Loaded cached package for: {}", package_path);
+        return Ok(package);
+    }
+
+    let package = compile_rust_package_uncached(package_path, options)?;
+    // The build may have created or updated the crate's lockfile, so key the entry by the
+    // sources as they were compiled
+    let stored = cache
+        .key(package_path, options)
+        .and_then(|key| cache.store(&key, &package));
+    if let Err(err) = stored {
+        println!("  ! Failed to cache compiled package: {err}");
+    }
+    Ok(package)
+}
+
+/// Compiles a Rust package to Miden, bypassing the package cache
+pub fn compile_rust_package_uncached(
+    package_path: &str,
+    options: &CompileOptions,
+) -> Result<Arc<Package>, DemoError> {
+    with_compiler_test(package_path, options, |test| test.compiled_package())
+}
+
+/// An intermediate compiler output that can be written out for inspection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum EmitStage {
+    /// Generated MASM source, written to `<pkg>.masm`
+    Masm,
+    /// Optimized HIR, written to `<pkg>.hir`
+    Hir,
+    /// The assembled MAST package, written to `<pkg>.masp`
+    Mast,
+}
+
+/// Compiles a Rust package, bypassing the cache, and writes the requested stages to
+/// `<emit_dir>/<pkg>/`
+pub fn compile_rust_package_with_emit(
+    package_path: &str,
+    options: &CompileOptions,
+    emit: &[EmitStage],
+    emit_dir: &Path,
+) -> Result<Arc<Package>, DemoError> {
+    // Named like the artifacts of `CompilerTestBuilder`
+    let name = Path::new(package_path)
+        .file_stem()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let out_dir = emit_dir.join(&name);
+    let emit = emit.to_vec();
+
+    with_compiler_test(package_path, options, move |test| {
+        let package = test.compiled_package()?;
+        std::fs::create_dir_all(&out_dir)?;
+        for stage in emit {
+            let path = match stage {
+                EmitStage::Masm => {
+                    let path = out_dir.join(format!("{name}.masm"));
+                    std::fs::write(&path, test.masm_src()?)?;
+                    path
+                }
+                EmitStage::Hir => {
+                    let path = out_dir.join(format!("{name}.hir"));
+                    std::fs::write(&path, test.hir_src()?)?;
+                    path
+                }
+                EmitStage::Mast => {
+                    let path = out_dir.join(format!("{name}.{PACKAGE_EXTENSION}"));
+                    save_package(&path, &package)?;
+                    path
+                }
+            };
+            println!("  Wrote {}", path.display());
+        }
+        Ok(package)
+    })
+}
+
+/// Builds a [`CompilerTest`] for `package_path` and runs `f` on it
+fn with_compiler_test<T, F>(
+    package_path: &str,
+    options: &CompileOptions,
+    f: F,
+) -> Result<T, DemoError>
+where
+    T: Send + 'static,
+    F: FnOnce(&mut CompilerTest) -> Result<T, DemoError> + Send + 'static,
+{
+    use midenc_frontend_wasm::WasmTranslationConfig;
+
+    println!("  Compiling Rust package at: {}", package_path);
+
+    // Run the compilation in a blocking thread to avoid runtime conflicts
+    let package_path = package_path.to_string();
+    let thread_package_path = package_path.clone();
+    let options = options.clone();
+    let handle = std::thread::spawn(move || {
+        let package_path = thread_package_path;
+        // Use the exact same approach as CompilerTestBuilder::rust_source_cargo_miden
+        let config = WasmTranslationConfig::default();
+        let mut builder = CompilerTestBuilder::rust_source_cargo_miden(
+            &package_path,
+            config,
+            options.all_midenc_flags(),
+        );
+        builder
+            .with_release(options.release)
+            .with_rustflags(options.rustflags);
+        for (path, source) in options.masm_modules {
+            builder.with_masm_module(path, source);
+        }
+
+        let mut test = builder.build()?;
+        f(&mut test)
+    });
+
+    let output = handle.join().map_err(|_| DemoError::Compilation {
+        package: package_path,
+        message: "compilation thread panicked".to_string(),
+    })??;
+    println!("  ✓ Successfully compiled package");
+    Ok(output)
+}
+
+/// A package built by [`compile_rust_packages`]
+pub struct CompiledPackage {
+    pub path: PathBuf,
+    pub package: Arc<Package>,
+    /// Wall-clock time spent compiling or loading the package
+    pub elapsed: Duration,
+}
+
+impl CompiledPackage {
+    /// Returns the package, failing unless it is of the `expected` kind
+    pub fn package_of_kind(&self, expected: PackageKind) -> Result<Arc<Package>, DemoError> {
+        check_package_kind(&self.path, &self.package, expected)?;
+        Ok(self.package.clone())
+    }
+}
+
+/// Builds several packages, compiling independent ones at the same time
+///
+/// A crate listed in another crate's `[package.metadata.miden.dependencies]` is built before
+/// it. Paths ending in `.masp` are loaded as prebuilt packages. Results are returned in the
+/// order of `package_paths`.
+pub fn compile_rust_packages(
+    package_paths: &[impl AsRef<Path>],
+    options: &CompileOptions,
+) -> Result<Vec<CompiledPackage>, DemoError> {
+    let paths: Vec<PathBuf> = package_paths
+        .iter()
+        .map(|path| path.as_ref().to_path_buf())
+        .collect();
+    let canonical_paths: Vec<PathBuf> = paths.iter().map(|path| canonicalize(path)).collect();
+
+    // Dependencies of each package, as indices into `paths`
+    let mut dependencies: Vec<Vec<usize>> = Vec::with_capacity(paths.len());
+    for path in &paths {
+        let mut package_dependencies = vec![];
+        if path.is_dir() {
+            for dependency in MidenManifest::read(path)?.dependencies {
+                let dependency = canonicalize(&dependency);
+                if let Some(index) = canonical_paths.iter().position(|path| *path == dependency) {
+                    package_dependencies.push(index);
+                }
+            }
+        }
+        dependencies.push(package_dependencies);
+    }
+
+    let waves = dependency_waves(&dependencies).map_err(|pending| DemoError::Compilation {
+        package: pending
+            .into_iter()
+            .map(|index| paths[index].display().to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+        message: "dependency cycle between packages".to_string(),
+    })?;
+
+    let mut compiled: Vec<Option<CompiledPackage>> = paths.iter().map(|_| None).collect();
+    for ready in waves {
+        let results = std::thread::scope(|scope| {
+            let handles: Vec<_> = ready
+                .iter()
+                .map(|&index| {
+                    let path = &paths[index];
+                    scope.spawn(move || -> Result<CompiledPackage, DemoError> {
+                        let start = Instant::now();
+                        let package = load_or_compile_package(path, options)?;
+                        Ok(CompiledPackage {
+                            path: path.clone(),
+                            package,
+                            elapsed: start.elapsed(),
+                        })
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .zip(&ready)
+                .map(|(handle, &index)| {
+                    handle.join().unwrap_or_else(|_| {
+                        Err(DemoError::Compilation {
+                            package: paths[index].display().to_string(),
+                            message: "compilation thread panicked".to_string(),
+                        })
+                    })
+                })
+                .collect::<Vec<_>>()
+        });
+
+        for (index, result) in ready.into_iter().zip(results) {
+            let package = result?;
+            println!(
+                "  ✓ Built {} in {:.2?}",
+                package.path.display(),
+                package.elapsed
+            );
+            compiled[index] = Some(package);
+        }
+    }
+
+    Ok(compiled.into_iter().flatten().collect())
+}
+
+/// Groups packages into waves that can be built in parallel, each after the ones before it
+///
+/// `dependencies[i]` lists the indices of the packages package `i` depends on. If some
+/// packages depend on each other in a cycle, returns the indices of those that could not be
+/// scheduled.
+fn dependency_waves(dependencies: &[Vec<usize>]) -> Result<Vec<Vec<usize>>, Vec<usize>> {
+    let mut scheduled = vec![false; dependencies.len()];
+    let mut waves = vec![];
+    while scheduled.contains(&false) {
+        let ready: Vec<usize> = (0..dependencies.len())
+            .filter(|&index| {
+                !scheduled[index]
+                    && dependencies[index]
+                        .iter()
+                        .all(|&dependency| scheduled[dependency])
+            })
+            .collect();
+        if ready.is_empty() {
+            return Err((0..dependencies.len())
+                .filter(|&index| !scheduled[index])
+                .collect());
+        }
+        for &index in &ready {
+            scheduled[index] = true;
+        }
+        waves.push(ready);
+    }
+    Ok(waves)
+}
+
+fn canonicalize(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// CompilerTestBuilder implementation copied from integration tests
+pub struct CompilerTestBuilder {
+    config: midenc_frontend_wasm::WasmTranslationConfig,
+    source: CompilerTestInputType,
+    link_masm_modules: Vec<(LibraryPath, String)>,
+    midenc_flags: Vec<String>,
+    rustflags: Vec<std::borrow::Cow<'static, str>>,
+}
+
+pub enum CompilerTestInputType {
+    CargoMiden(CargoTest),
+}
+
+pub struct CargoTest {
+    project_dir: std::path::PathBuf,
+    name: std::borrow::Cow<'static, str>,
+    release: bool,
+}
+
+impl CargoTest {
+    pub fn new(
+        name: impl Into<std::borrow::Cow<'static, str>>,
+        project_dir: std::path::PathBuf,
+    ) -> Self {
+        Self {
+            project_dir,
+            name: name.into(),
+            release: true,
+        }
+    }
+}
+
+impl CompilerTestBuilder {
+    pub fn new(source: CompilerTestInputType) -> Self {
+        let _name = match &source {
+            CompilerTestInputType::CargoMiden(config) => config.name.as_ref(),
+        };
+        Self {
+            config: Default::default(),
+            source,
+            link_masm_modules: vec![],
+            midenc_flags: vec![],
+            rustflags: vec![],
+        }
+    }
+
+    pub fn rust_source_cargo_miden(
+        cargo_project_folder: impl AsRef<std::path::Path>,
+        config: midenc_frontend_wasm::WasmTranslationConfig,
+        midenc_flags: impl IntoIterator<Item = String>,
+    ) -> Self {
+        let name = cargo_project_folder
+            .as_ref()
+            .file_stem()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or("".to_string());
+        let mut builder = CompilerTestBuilder::new(CompilerTestInputType::CargoMiden(
+            CargoTest::new(name, cargo_project_folder.as_ref().to_path_buf()),
+        ));
+        builder.config = config;
+        builder.midenc_flags.extend(midenc_flags);
+        builder
+    }
+
+    pub fn with_release(&mut self, release: bool) -> &mut Self {
+        match &mut self.source {
+            CompilerTestInputType::CargoMiden(config) => config.release = release,
+        }
+        self
+    }
+
+    /// Links a hand-written MASM module into the compiled package
+    pub fn with_masm_module(&mut self, path: LibraryPath, source: impl Into<String>) -> &mut Self {
+        self.link_masm_modules.push((path, source.into()));
+        self
+    }
+
+    /// Replaces the `RUSTFLAGS` used for the Rust to Wasm build
+    pub fn with_rustflags(&mut self, rustflags: impl IntoIterator<Item = String>) -> &mut Self {
+        self.rustflags = rustflags.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn build(mut self) -> Result<CompilerTest, DemoError> {
+        use midenc_session::{InputFile, InputType};
+        use std::ffi::OsStr;
+        use std::process::{Command, Stdio};
+
+        // Set up the command used to compile the test inputs (Rust -> Wasm)
+        let mut command = Command::new("cargo");
+        command.arg("miden").arg("build");
+
+        // Extract the directory in which source code exists
+        let project_dir = match &self.source {
+            CompilerTestInputType::CargoMiden(config) => &config.project_dir,
+        };
+        let name = match &self.source {
+            CompilerTestInputType::CargoMiden(config) => config.name.to_string(),
+        };
+        let compilation_error = |message: String| DemoError::Compilation {
+            package: name.clone(),
+            message,
+        };
+
+        // Cargo-based configuration
+        let CompilerTestInputType::CargoMiden(config) = &self.source;
+        {
+            let manifest_path = project_dir.join("Cargo.toml");
+            command.arg("--manifest-path").arg(manifest_path);
+            if config.release {
+                command.arg("--release");
+            }
+        }
+
+        // Set RUSTFLAGS
+        if !self.rustflags.is_empty() {
+            let mut flags = String::with_capacity(
+                self.rustflags.iter().map(|flag| flag.len()).sum::<usize>() + self.rustflags.len(),
+            );
+            for (i, flag) in self.rustflags.iter().enumerate() {
+                if i > 0 {
+                    flags.push(' ');
+                }
+                flags.push_str(flag.as_ref());
+            }
+            command.env("RUSTFLAGS", flags);
+        }
+
+        command.stdout(Stdio::piped());
+
+        // Build using cargo-miden
+        let mut args = vec![command.get_program().to_string_lossy().into_owned()];
+        let cmd_args: Vec<String> = command
+            .get_args()
+            .collect::<Vec<&OsStr>>()
+            .iter()
+            .map(|s| s.to_string_lossy().into_owned())
+            .collect();
+        args.extend(cmd_args);
+
+        let build_output = cargo_miden::run(args.into_iter(), cargo_miden::OutputType::Wasm)
+            .map_err(|err| compilation_error(format!("'cargo miden build' failed: {err}")))?
+            .ok_or_else(|| compilation_error("'cargo miden build' returned no output".to_string()))?
+            .unwrap_build_output();
+
+        let (wasm_artifact_path, mut extra_midenc_flags) = match build_output {
+            cargo_miden::BuildOutput::Wasm {
+                artifact_path,
+                midenc_flags,
+            } => (artifact_path, midenc_flags),
+            other => {
+                return Err(compilation_error(format!(
+                    "expected Wasm output, got {:?}",
+                    other
+                )))
+            }
+        };
+
+        self.midenc_flags.append(&mut extra_midenc_flags);
+        let input_file = InputFile::from_path(wasm_artifact_path)
+            .map_err(|err| compilation_error(format!("failed to read Wasm artifact: {err}")))?;
+        let mut inputs = vec![input_file];
+        inputs.extend(self.link_masm_modules.into_iter().map(|(path, content)| {
+            let path = path.to_string();
+            InputFile::new(
+                midenc_session::FileType::Masm,
+                InputType::Stdin {
+                    name: path.into(),
+                    input: content.into_bytes(),
+                },
+            )
+        }));
+
+        let context = default_context(inputs, &self.midenc_flags);
+        Ok(CompilerTest {
+            context,
+            name,
+            hir: None,
+            masm_src: None,
+            ir_masm_program: None,
+            package: None,
+        })
+    }
+}
+
+pub struct CompilerTest {
+    pub context: std::rc::Rc<midenc_hir::Context>,
+    name: String,
+    hir: Option<midenc_compile::LinkOutput>,
+    masm_src: Option<String>,
+    ir_masm_program: Option<Result<Arc<midenc_codegen_masm::MasmComponent>, String>>,
+    package: Option<Result<Arc<miden_mast_package::Package>, String>>,
+}
+
+impl CompilerTest {
+    pub fn compiled_package(&mut self) -> Result<Arc<miden_mast_package::Package>, DemoError> {
+        if self.package.is_none() {
+            if let Err(message) = self.compile_wasm_to_masm_program() {
+                self.package = Some(Err(message));
+            }
+        }
+        match self.package.as_ref() {
+            Some(Ok(prog)) => Ok(prog.clone()),
+            Some(Err(msg)) => Err(DemoError::Compilation {
+                package: self.name.clone(),
+                message: msg.clone(),
+            }),
+            None => unreachable!("package is set by compile_wasm_to_masm_program"),
+        }
+    }
+
+    /// Returns the generated MASM source, compiling the package first if needed
+    pub fn masm_src(&mut self) -> Result<&str, DemoError> {
+        self.compiled_package()?;
+        Ok(self
+            .masm_src
+            .as_deref()
+            .expect("masm source is set once the package is compiled"))
+    }
+
+    /// Returns the optimized HIR, translating the Wasm first if needed
+    pub fn hir(&mut self) -> Result<&midenc_compile::LinkOutput, DemoError> {
+        let name = self.name.clone();
+        self.link_output()
+            .map_err(|message| DemoError::Compilation {
+                package: name,
+                message,
+            })
+    }
+
+    /// Returns the optimized HIR component as text
+    pub fn hir_src(&mut self) -> Result<String, DemoError> {
+        Ok(self.hir()?.component.borrow().to_string())
+    }
+
+    /// Returns the MASM component with an entrypoint, if the package is a program
+    pub fn ir_masm_program(
+        &mut self,
+    ) -> Result<Option<Arc<midenc_codegen_masm::MasmComponent>>, DemoError> {
+        self.compiled_package()?;
+        match &self.ir_masm_program {
+            Some(Ok(program)) => Ok(Some(program.clone())),
+            Some(Err(message)) => Err(DemoError::Compilation {
+                package: self.name.clone(),
+                message: message.clone(),
+            }),
+            None => Ok(None),
+        }
+    }
+
+    fn link_output(&mut self) -> Result<&midenc_compile::LinkOutput, String> {
+        use midenc_compile::compile_to_optimized_hir;
+
+        if self.hir.is_none() {
+            let link_output = compile_to_optimized_hir(self.context.clone())
+                .map_err(|err| format!("failed to translate wasm to hir component: {err}"))?;
+            self.hir = Some(link_output);
+        }
+        Ok(self.hir.as_ref().unwrap())
+    }
+
+    fn compile_wasm_to_masm_program(&mut self) -> Result<(), String> {
+        use midenc_compile::{compile_link_output_to_masm_with_pre_assembly_stage, CodegenOutput};
+        use midenc_hir::Context;
+
+        let mut src = None;
+        let mut masm_program = None;
+        let mut stage = |output: CodegenOutput, _context: std::rc::Rc<Context>| {
+            src = Some(output.component.to_string());
+            if output.component.entrypoint.is_some() {
+                masm_program = Some(Arc::clone(&output.component));
+            }
+            Ok(output)
+        };
+
+        let link_output = self.link_output()?.clone();
+        let package = compile_link_output_to_masm_with_pre_assembly_stage(link_output, &mut stage)
+            .map_err(format_report)?
+            .unwrap_mast();
+
+        if src.is_none() {
+            return Err("failed to pretty print masm artifact".to_string());
+        }
+        self.masm_src = src;
+        self.ir_masm_program = masm_program.map(Ok);
+        self.package = Some(Ok(Arc::new(package)));
+        Ok(())
+    }
+}
+
+/// Create a valid [Context] for `inputs` with `argv`, with useful defaults.
+pub fn default_context<S, I>(inputs: I, argv: &[S]) -> std::rc::Rc<midenc_hir::Context>
+where
+    I: IntoIterator<Item = midenc_session::InputFile>,
+    S: AsRef<str>,
+{
+    let session = default_session(inputs, argv);
+    let context = std::rc::Rc::new(midenc_hir::Context::new(session));
+    midenc_codegen_masm::register_dialect_hooks(&context);
+    context
+}
+
+/// Create a valid [Session] for compiling `inputs` with `argv`, with useful defaults.
+pub fn default_session<S, I>(inputs: I, argv: &[S]) -> std::rc::Rc<midenc_session::Session>
+where
+    I: IntoIterator<Item = midenc_session::InputFile>,
+    S: AsRef<str>,
+{
+    use midenc_session::diagnostics::reporting::{self, ReportHandlerOpts};
+
+    let result = reporting::set_hook(Box::new(|_| {
+        let wrapping_width = 300; // avoid wrapped file paths in the backtrace
+        Box::new(ReportHandlerOpts::new().width(wrapping_width).build())
+    }));
+    if result.is_ok() {
+        reporting::set_panic_hook();
+    }
+
+    let argv = argv.iter().map(|arg| arg.as_ref());
+    let session = midenc_compile::Compiler::new_session(inputs, None, argv);
+    std::rc::Rc::new(session)
+}
+
+fn format_report(err: impl std::fmt::Display) -> String {
+    format!("{}", err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::dependency_waves;
+
+    #[test]
+    fn independent_packages_share_a_wave() {
+        assert_eq!(
+            dependency_waves(&[vec![], vec![], vec![]]),
+            Ok(vec![vec![0, 1, 2]])
+        );
+    }
+
+    #[test]
+    fn dependencies_come_first() {
+        // 0 -> 1 -> 2, and 3 -> 1
+        let dependencies = [vec![1], vec![2], vec![], vec![1]];
+        assert_eq!(
+            dependency_waves(&dependencies),
+            Ok(vec![vec![2], vec![1], vec![0, 3]])
+        );
+    }
+
+    #[test]
+    fn no_packages_means_no_waves() {
+        assert_eq!(dependency_waves(&[]), Ok(vec![]));
+    }
+
+    #[test]
+    fn cycles_are_reported() {
+        // 0 is fine, 1 <-> 2 form a cycle and 3 depends on the cycle
+        let dependencies = [vec![], vec![2], vec![1], vec![1]];
+        assert_eq!(dependency_waves(&dependencies), Err(vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn self_dependencies_are_cycles() {
+        assert_eq!(dependency_waves(&[vec![0]]), Err(vec![0]));
+    }
+}
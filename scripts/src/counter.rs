@@ -0,0 +1,97 @@
+//! Helpers for the counter contract example
+
+use std::sync::Arc;
+
+use miden_client::{
+    account::Account,
+    transaction::{OutputNote, TransactionRequestBuilder},
+    Client, Felt, Word,
+};
+use miden_mast_package::Package;
+use miden_objects::FieldElement;
+
+use crate::{
+    error::DemoError,
+    helpers::{
+        create_account_with_component, create_note_from_package, AccountCreationConfig,
+        NoteCreationConfig,
+    },
+    keystore::KeyStore,
+    storage::{ComponentStorage, StorageInit},
+};
+
+/// Storage map of the counter contract, as described in `examples/counter-contract`
+pub const COUNTER_STORAGE_MAP: &str = "counter contract storage map";
+
+/// Key of the counter value inside [`COUNTER_STORAGE_MAP`]
+pub fn counter_key() -> Word {
+    Word::from([Felt::ZERO, Felt::ZERO, Felt::ZERO, Felt::ONE])
+}
+
+/// Returns the value held by the counter contract built from `package` in `account`
+pub fn counter_value(account: &Account, package: &Package) -> Result<u64, DemoError> {
+    let word = ComponentStorage::new(account, package)?
+        .get_map_item(COUNTER_STORAGE_MAP, counter_key())?;
+    Ok(word.last().unwrap().as_int())
+}
+
+/// Asserts that the counter contract built from `package` in `account` holds `expected`
+pub fn assert_counter_storage(account: &Account, package: &Package, expected: u64) {
+    let val = counter_value(account, package)
+        .unwrap_or_else(|err| panic!("Failed to read the counter value: {err}"));
+    assert_eq!(
+        val, expected,
+        "Counter value mismatch. Expected: {}, Got: {}",
+        expected, val
+    );
+}
+
+/// Creates a counter account with `config`, then creates and consumes a counter note
+///
+/// The counter starts at 1; the returned account is the state after the increment, read from
+/// the local store, which is the only copy of the state of a private account.
+pub async fn deploy_and_increment_counter(
+    client: &mut Client,
+    keystore: Arc<dyn KeyStore>,
+    contract_package: Arc<Package>,
+    note_package: Arc<Package>,
+    config: AccountCreationConfig,
+) -> Result<Account, DemoError> {
+    let value = Word::from([Felt::ZERO, Felt::ZERO, Felt::ZERO, Felt::ONE]);
+    let config = AccountCreationConfig {
+        storage_init: Some(StorageInit::default().with_map_entry(
+            COUNTER_STORAGE_MAP,
+            counter_key(),
+            value,
+        )),
+        ..config
+    };
+    let account =
+        create_account_with_component(client, keystore, contract_package.clone(), config).await?;
+
+    let note = create_note_from_package(
+        client,
+        note_package,
+        account.id(),
+        NoteCreationConfig::default(),
+    )?;
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note.clone())])
+        .build()?;
+    let tx_result = client.new_transaction(account.id(), request).await?;
+    client.submit_transaction(tx_result).await?;
+
+    let request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(note, None)])
+        .build()?;
+    let tx_result = client.new_transaction(account.id(), request).await?;
+    client.submit_transaction(tx_result).await?;
+
+    client.sync_state().await?;
+    let account: Account = client
+        .get_account(account.id())
+        .await?
+        .ok_or(DemoError::AccountNotFound(account.id()))?
+        .into();
+    Ok(account)
+}
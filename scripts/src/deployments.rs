@@ -0,0 +1,354 @@
+//! Registry of deployed accounts and notes, persisted as `deployments.json`
+//!
+//! Every flow records the accounts and notes it creates under a name (`counter`, `alice`, ...),
+//! so later commands can refer to them by that name instead of a copied account ID.
+
+use std::{
+    collections::BTreeMap,
+    fmt, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use miden_client::{
+    account::{Account, AccountId, AccountStorageMode, AccountType, StorageSlot},
+    note::{Note, NoteId},
+    transaction::TransactionId,
+    Client,
+};
+use miden_mast_package::Package;
+use serde::{Deserialize, Serialize};
+
+use crate::{auth::AuthScheme, error::DemoError, keystore::KeyStore, network::Network};
+
+/// Default location of the registry, relative to the working directory
+pub const DEFAULT_DEPLOYMENTS_PATH: &str = "deployments.json";
+
+/// Accounts and notes created by the scripts, grouped by network
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Deployments {
+    /// File the registry was loaded from and is saved to
+    #[serde(skip)]
+    path: PathBuf,
+    /// Deployments keyed by network name (`mock`, `local`, `testnet`)
+    #[serde(default)]
+    pub networks: BTreeMap<String, NetworkDeployments>,
+}
+
+/// Accounts and notes created on one network
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NetworkDeployments {
+    #[serde(default)]
+    pub accounts: BTreeMap<String, DeployedAccount>,
+    #[serde(default)]
+    pub notes: BTreeMap<String, DeployedNote>,
+}
+
+/// A deployed account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeployedAccount {
+    /// Bech32 account ID
+    pub id: String,
+    /// Account type, e.g. `RegularAccountUpdatableCode`
+    #[serde(default)]
+    pub account_type: String,
+    /// `public`, `private` or `network`; the state of private accounts only exists in the
+    /// local store they were created with
+    #[serde(default)]
+    pub storage_mode: String,
+    /// Auth scheme of the account, e.g. `rpo-falcon512` or `multisig:3`
+    #[serde(default = "default_auth")]
+    pub auth: String,
+    /// Digests of the component packages the account was built from
+    pub packages: Vec<String>,
+    /// Kind of each storage slot (`value` or `map`), in slot order
+    pub storage: Vec<String>,
+    /// Transactions executed against the account, oldest first
+    #[serde(default)]
+    pub transactions: Vec<String>,
+}
+
+/// What an account registered by an earlier run has to match to be reused
+#[derive(Debug, Clone, Copy)]
+pub struct AccountRequirements<'a> {
+    /// Component packages the account was built from, in order
+    pub packages: &'a [&'a Package],
+    /// Auth scheme of the account
+    pub auth: AuthScheme,
+    pub account_type: AccountType,
+    pub storage_mode: AccountStorageMode,
+}
+
+/// A note created by one of the scripts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeployedNote {
+    /// Note ID in hex
+    pub id: String,
+    /// Bech32 ID of the account that created the note
+    pub sender: String,
+    /// Digest of the note script package, if the note was built from one
+    pub package: Option<String>,
+    /// Transactions that created and consumed the note
+    #[serde(default)]
+    pub transactions: Vec<String>,
+}
+
+impl Deployments {
+    /// Loads the registry at `path`, or returns an empty one if the file does not exist
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, DemoError> {
+        let path = path.as_ref();
+        let mut deployments = if path.exists() {
+            let contents = fs::read_to_string(path)?;
+            serde_json::from_str(&contents).map_err(|err| DemoError::InvalidDeployments {
+                path: path.to_path_buf(),
+                message: err.to_string(),
+            })?
+        } else {
+            Self::default()
+        };
+        deployments.path = path.to_path_buf();
+        Ok(deployments)
+    }
+
+    /// Writes the registry back to the file it was loaded from
+    pub fn save(&self) -> Result<(), DemoError> {
+        let contents =
+            serde_json::to_string_pretty(self).map_err(|err| self.invalid(err.to_string()))?;
+        fs::write(&self.path, contents)?;
+        Ok(())
+    }
+
+    fn invalid(&self, message: String) -> DemoError {
+        DemoError::InvalidDeployments {
+            path: self.path.clone(),
+            message,
+        }
+    }
+
+    /// Returns the deployments on `network`
+    pub fn network(&self, network: Network) -> Option<&NetworkDeployments> {
+        self.networks.get(&network.to_string())
+    }
+
+    fn network_mut(&mut self, network: Network) -> &mut NetworkDeployments {
+        self.networks.entry(network.to_string()).or_default()
+    }
+
+    /// Returns the account registered as `name` on `network`
+    pub fn account(&self, network: Network, name: &str) -> Option<&DeployedAccount> {
+        self.network(network)?.accounts.get(name)
+    }
+
+    /// Returns the ID of the account registered as `name` on `network`
+    pub fn account_id(&self, network: Network, name: &str) -> Result<AccountId, DemoError> {
+        let account = self
+            .account(network, name)
+            .ok_or_else(|| DemoError::UnknownAccount {
+                name: name.to_string(),
+                network,
+            })?;
+        let (_, id) = AccountId::from_bech32(&account.id).map_err(|err| {
+            self.invalid(format!(
+                "account '{name}' has invalid ID '{}': {err}",
+                account.id
+            ))
+        })?;
+        Ok(id)
+    }
+
+    /// Resolves an account given by ID or by registered name
+    pub fn resolve(&self, network: Network, account: &AccountRef) -> Result<AccountId, DemoError> {
+        match account {
+            AccountRef::Id(id) => Ok(*id),
+            AccountRef::Name(name) => self.account_id(network, name),
+        }
+    }
+
+    /// Returns the account registered as `name` on `network`, if it can still be used
+    ///
+    /// The account has to meet the `requirements`, be tracked by the client store, and every
+    /// signing key of its auth component has to be in `keystore`. Otherwise `None` is returned
+    /// and the caller is expected to create a new account.
+    pub async fn find_account(
+        &self,
+        client: &mut Client,
+        keystore: &dyn KeyStore,
+        network: Network,
+        name: &str,
+        requirements: &AccountRequirements<'_>,
+    ) -> Result<Option<Account>, DemoError> {
+        let Some(deployed) = self.account(network, name) else {
+            return Ok(None);
+        };
+        if deployed.auth != requirements.auth.to_string()
+            || deployed.account_type != format!("{:?}", requirements.account_type)
+            || deployed.storage_mode != requirements.storage_mode.to_string()
+        {
+            return Ok(None);
+        }
+        let digests: Vec<String> = requirements
+            .packages
+            .iter()
+            .map(|package| package.digest().to_string())
+            .collect();
+        if deployed.packages != digests {
+            return Ok(None);
+        }
+        let id = self.account_id(network, name)?;
+        let Some(record) = client.get_account(id).await? else {
+            return Ok(None);
+        };
+        let account: Account = record.into();
+        if account.id().account_type() != requirements.account_type
+            || account.id().storage_mode() != requirements.storage_mode
+        {
+            return Ok(None);
+        }
+
+        for public_key in requirements.auth.public_keys(&account)? {
+            if keystore.get_key(public_key)?.is_none() {
+                return Ok(None);
+            }
+        }
+        Ok(Some(account))
+    }
+
+    /// Like [`find_account`](Self::find_account), but never reuses an account if `fresh` is set
+    pub async fn reusable_account(
+        &self,
+        client: &mut Client,
+        keystore: &dyn KeyStore,
+        network: Network,
+        name: &str,
+        requirements: &AccountRequirements<'_>,
+        fresh: bool,
+    ) -> Result<Option<Account>, DemoError> {
+        if fresh {
+            return Ok(None);
+        }
+        self.find_account(client, keystore, network, name, requirements)
+            .await
+    }
+
+    /// Registers `account` as `name`, replacing any earlier account with that name
+    pub fn record_account(
+        &mut self,
+        network: Network,
+        name: impl Into<String>,
+        account: &Account,
+        packages: &[&Package],
+        auth: &AuthScheme,
+    ) {
+        let storage = account
+            .storage()
+            .slots()
+            .iter()
+            .map(|slot| match slot {
+                StorageSlot::Value(_) => "value".to_string(),
+                StorageSlot::Map(_) => "map".to_string(),
+            })
+            .collect();
+        let deployed = DeployedAccount {
+            id: account.id().to_bech32(network.network_id()),
+            account_type: format!("{:?}", account.id().account_type()),
+            storage_mode: account.id().storage_mode().to_string(),
+            auth: auth.to_string(),
+            packages: packages
+                .iter()
+                .map(|package| package.digest().to_string())
+                .collect(),
+            storage,
+            transactions: vec![],
+        };
+        self.network_mut(network)
+            .accounts
+            .insert(name.into(), deployed);
+    }
+
+    /// Registers `note` as `name`, replacing any earlier note with that name
+    pub fn record_note(
+        &mut self,
+        network: Network,
+        name: impl Into<String>,
+        note: &Note,
+        package: Option<&Package>,
+    ) {
+        let deployed = DeployedNote {
+            id: note.id().to_hex(),
+            sender: note.metadata().sender().to_bech32(network.network_id()),
+            package: package.map(|package| package.digest().to_string()),
+            transactions: vec![],
+        };
+        self.network_mut(network)
+            .notes
+            .insert(name.into(), deployed);
+    }
+
+    /// Appends `tx_id` to the transactions of every account with `account_id` on `network`
+    pub fn record_account_transaction(
+        &mut self,
+        network: Network,
+        account_id: AccountId,
+        tx_id: TransactionId,
+    ) {
+        let id = account_id.to_bech32(network.network_id());
+        for account in self.network_mut(network).accounts.values_mut() {
+            if account.id == id {
+                account.transactions.push(tx_id.to_hex());
+            }
+        }
+    }
+
+    /// Appends `tx_id` to the transactions of every note with `note_id` on `network`
+    pub fn record_note_transaction(
+        &mut self,
+        network: Network,
+        note_id: NoteId,
+        tx_id: TransactionId,
+    ) {
+        let id = note_id.to_hex();
+        for note in self.network_mut(network).notes.values_mut() {
+            if note.id == id {
+                note.transactions.push(tx_id.to_hex());
+            }
+        }
+    }
+}
+
+/// Accounts registered before the auth scheme was recorded all used a single Falcon key
+fn default_auth() -> String {
+    AuthScheme::RpoFalcon512.to_string()
+}
+
+/// An account given on the command line, either by ID or by its name in the registry
+#[derive(Debug, Clone)]
+pub enum AccountRef {
+    Id(AccountId),
+    Name(String),
+}
+
+impl FromStr for AccountRef {
+    type Err = String;
+
+    /// Parses a bech32 or hex account ID, treating anything else as a registry name
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok((_, id)) = AccountId::from_bech32(s) {
+            return Ok(AccountRef::Id(id));
+        }
+        if s.starts_with("0x") {
+            return AccountId::from_hex(s)
+                .map(AccountRef::Id)
+                .map_err(|err| format!("invalid account ID '{s}': {err}"));
+        }
+        Ok(AccountRef::Name(s.to_string()))
+    }
+}
+
+impl fmt::Display for AccountRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AccountRef::Id(id) => write!(f, "{id}"),
+            AccountRef::Name(name) => f.write_str(name),
+        }
+    }
+}
@@ -0,0 +1,104 @@
+//! Error type shared by all helpers
+
+use std::path::PathBuf;
+
+use miden_client::{
+    account::AccountId, keystore::KeyStoreError, transaction::TransactionRequestError, ClientError,
+};
+use miden_objects::{utils::DeserializationError, AccountError, AssetError, NoteError};
+
+use crate::{network::Network, package::PackageKind};
+
+/// Errors returned by the helpers in this crate
+#[derive(Debug, thiserror::Error)]
+pub enum DemoError {
+    /// Compiling a Rust crate to a Miden package failed
+    #[error("failed to compile '{package}': {message}")]
+    Compilation { package: String, message: String },
+    /// A contract crate's `Cargo.toml` could not be parsed
+    #[error("invalid manifest {}: {message}", path.display())]
+    InvalidManifest { path: PathBuf, message: String },
+    /// A package was used for something its kind does not support
+    #[error("expected a '{expected}' package, got '{actual}'")]
+    WrongPackageKind {
+        expected: PackageKind,
+        actual: String,
+    },
+    /// An account package carries no component metadata
+    #[error("package '{0}' has no account component metadata")]
+    MissingComponentMetadata(String),
+    /// An account does not contain the component built from a package
+    #[error("account does not contain component '{0}'")]
+    ComponentNotInAccount(String),
+    /// A component's metadata declares no storage slot with this name
+    #[error("component '{component}' has no storage slot named '{name}'")]
+    UnknownStorageSlot { component: String, name: String },
+    /// Several storage slots of a component match this name or description
+    #[error("component '{component}' has several storage slots named '{name}'")]
+    AmbiguousStorageSlot { component: String, name: String },
+    /// Initial storage values are malformed or do not match the component's declared storage
+    #[error("invalid initial storage: {0}")]
+    InvalidStorageInit(String),
+    /// A component's storage slots do not match the layout its metadata declares
+    #[error("storage of component '{component}' does not match its metadata: {message}")]
+    StorageLayout { component: String, message: String },
+    /// A component does not support the type of account it is added to
+    #[error("component '{component}' does not support {account_type} accounts")]
+    UnsupportedAccountType {
+        component: String,
+        account_type: String,
+    },
+    /// A per-component setting was given on an account config used with several components
+    #[error("'{0}' is set per component; give it on each ComponentConfig instead")]
+    ComponentSettingOnAccountConfig(&'static str),
+    /// The account component metadata of a package could not be decoded
+    #[error("failed to decode account component metadata: {0}")]
+    MetadataDecoding(DeserializationError),
+    /// A serialized package could not be decoded
+    #[error("failed to decode package: {0}")]
+    PackageDecoding(DeserializationError),
+    /// The deployments registry could not be read or written
+    #[error("invalid deployments file {}: {message}", path.display())]
+    InvalidDeployments { path: PathBuf, message: String },
+    /// No account with this name is registered for the network
+    #[error("no account named '{name}' is registered for {network}")]
+    UnknownAccount { name: String, network: Network },
+    /// An encrypted keystore was requested but no passphrase was given
+    #[error("encrypted keystore requested but ${0} is not set")]
+    MissingPassphrase(&'static str),
+    /// An encrypted keystore was opened with a different passphrase than it was created with
+    #[error("wrong passphrase for the encrypted keystore in {}", .0.display())]
+    WrongPassphrase(PathBuf),
+    /// An encrypted keystore was opened without its passphrase
+    #[error("{} is an encrypted keystore; pass --encrypted-keystore", .0.display())]
+    EncryptedKeyStore(PathBuf),
+    /// `--seed` was given for a store that already has state, so seeded draws would repeat
+    #[error("--seed needs a fresh store, but {} already exists", .0.display())]
+    SeededStoreNotFresh(PathBuf),
+    /// An account is not tracked by the client store
+    #[error("account {0} is not tracked by the local store")]
+    AccountNotFound(AccountId),
+    /// An account expected to be a basic fungible faucet is not one
+    #[error("account {0} is not a basic fungible faucet")]
+    NotAFungibleFaucet(AccountId),
+    /// A MASM script generated by the helpers failed to compile
+    #[error("failed to compile script: {0}")]
+    ScriptCompilation(String),
+    /// A serialized note could not be decoded
+    #[error("failed to decode note: {0}")]
+    NoteDecoding(DeserializationError),
+    #[error("keystore error: {0}")]
+    KeyStore(#[from] KeyStoreError),
+    #[error("client error: {0}")]
+    Client(#[from] ClientError),
+    #[error("account error: {0}")]
+    Account(#[from] AccountError),
+    #[error("asset error: {0}")]
+    Asset(#[from] AssetError),
+    #[error("note error: {0}")]
+    Note(#[from] NoteError),
+    #[error("transaction request error: {0}")]
+    TransactionRequest(#[from] TransactionRequestError),
+    #[error("i/o error: {0}")]
+    Io(#[from] std::io::Error),
+}
@@ -0,0 +1,336 @@
+//! Faucet helpers: creating fungible and non-fungible faucets, minting, burning and reading
+//! fungible supply
+
+use std::sync::{Arc, OnceLock};
+
+use miden_client::{
+    account::{
+        component::BasicFungibleFaucet, Account, AccountId, AccountStorageMode, AccountType,
+    },
+    asset::{FungibleAsset, NonFungibleAsset, NonFungibleAssetDetails, TokenSymbol},
+    crypto::FeltRng,
+    note::{Note, NoteAssets, NoteInputs, NoteMetadata, NoteRecipient, NoteScript},
+    transaction::{OutputNote, TransactionId, TransactionRequestBuilder, TransactionScript},
+    Client, Felt, Word,
+};
+use miden_lib::transaction::TransactionKernel;
+use miden_mast_package::Package;
+use miden_objects::account::AccountComponent;
+
+use crate::{
+    auth::AuthScheme,
+    error::DemoError,
+    helpers::{build_and_register, create_note_from_package, note_script, NoteCreationConfig},
+    keystore::KeyStore,
+    wallet::{send_notes, AssetTransferConfig},
+};
+
+/// Account component of a non-fungible faucet
+///
+/// `miden-lib` has no standard non-fungible faucet, so this one only exposes the minting
+/// procedure the mint transaction script calls.
+const NON_FUNGIBLE_FAUCET: &str = "
+use.miden::faucet
+use.miden::tx
+
+#! Mints a non-fungible asset of this faucet into the note at `note_idx`
+#!
+#! Inputs:  [ASSET, note_idx, pad(11)]
+#! Outputs: [ASSET, note_idx, pad(11)]
+export.mint_to_note
+    exec.faucet::mint
+    exec.tx::add_asset_to_note
+end
+";
+
+/// Procedure of [`NON_FUNGIBLE_FAUCET`] the mint transaction script calls
+const MINT_TO_NOTE: &str = "mint_to_note";
+
+/// Note script that burns the note's asset when the issuing faucet consumes the note
+const BURN_NOTE: &str = "
+use.miden::note
+use.miden::contracts::faucets::basic_fungible->faucet
+
+begin
+    padw padw padw padw
+    push.0 exec.note::get_assets
+    # => [num_assets, dest_ptr, pad(16)]
+
+    drop mem_loadw
+    # => [ASSET, pad(12)]
+
+    call.faucet::burn
+    dropw dropw dropw dropw
+end
+";
+
+/// Storage slot the kernel reserves in fungible faucets; its last felt is the total issuance
+const FAUCET_SYSDATA_SLOT: u8 = 0;
+
+/// Issued and maximum supply of a fungible faucet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FaucetSupply {
+    pub decimals: u8,
+    /// Tokens currently in circulation, i.e. minted minus burned
+    pub issued: u64,
+    pub max_supply: u64,
+}
+
+/// Create a fungible faucet account
+pub async fn create_fungible_faucet_account(
+    client: &mut Client,
+    keystore: Arc<dyn KeyStore>,
+    token_symbol: TokenSymbol,
+    decimals: u8,
+    max_supply: Felt,
+    auth: AuthScheme,
+) -> Result<Account, DemoError> {
+    let faucet = BasicFungibleFaucet::new(token_symbol, decimals, max_supply)?;
+    build_and_register(client, keystore, auth, |builder| {
+        builder
+            .account_type(AccountType::FungibleFaucet)
+            .storage_mode(AccountStorageMode::Public)
+            .with_component(faucet)
+    })
+    .await
+}
+
+/// Returns whether `account` is a basic fungible faucet issuing `token_symbol` with `decimals`
+/// and `max_supply`
+///
+/// Used to check that a faucet registered by an earlier run still issues the requested token
+/// before reusing it.
+pub fn is_fungible_faucet_for(
+    account: &Account,
+    token_symbol: TokenSymbol,
+    decimals: u8,
+    max_supply: Felt,
+) -> bool {
+    BasicFungibleFaucet::try_from(account.clone()).is_ok_and(|faucet| {
+        faucet.symbol() == token_symbol
+            && faucet.decimals() == decimals
+            && faucet.max_supply() == max_supply
+    })
+}
+
+/// Create a non-fungible faucet account
+pub async fn create_non_fungible_faucet_account(
+    client: &mut Client,
+    keystore: Arc<dyn KeyStore>,
+    auth: AuthScheme,
+) -> Result<Account, DemoError> {
+    let component = non_fungible_faucet_component()?.component.clone();
+    build_and_register(client, keystore, auth, |builder| {
+        builder
+            .account_type(AccountType::NonFungibleFaucet)
+            .storage_mode(AccountStorageMode::Public)
+            .with_component(component)
+    })
+    .await
+}
+
+/// The non-fungible faucet component, with the MAST root of its minting procedure
+struct NonFungibleFaucetComponent {
+    component: AccountComponent,
+    mint_root: Word,
+}
+
+/// Compiles [`NON_FUNGIBLE_FAUCET`] on first use and returns the component built from it
+fn non_fungible_faucet_component() -> Result<&'static NonFungibleFaucetComponent, DemoError> {
+    static COMPONENT: OnceLock<NonFungibleFaucetComponent> = OnceLock::new();
+    if let Some(component) = COMPONENT.get() {
+        return Ok(component);
+    }
+
+    let component =
+        AccountComponent::compile(NON_FUNGIBLE_FAUCET, TransactionKernel::assembler(), vec![])?
+            .with_supported_type(AccountType::NonFungibleFaucet);
+    let library = component.library();
+    let mint_export = library
+        .exports()
+        .find(|export| export.name.as_str() == MINT_TO_NOTE)
+        .ok_or_else(|| {
+            DemoError::ScriptCompilation(format!(
+                "the non-fungible faucet component does not export '{MINT_TO_NOTE}'"
+            ))
+        })?;
+    let mint_root = library.mast_forest()[library.get_export_node_id(mint_export)].digest();
+    Ok(COMPONENT.get_or_init(|| NonFungibleFaucetComponent {
+        component,
+        mint_root,
+    }))
+}
+
+/// Mints the non-fungible asset described by `data` to `recipient` through a P2ID note
+///
+/// The note is built from `note_package`. Returns the transaction ID, the note for the
+/// recipient to consume and the minted asset.
+pub async fn mint_non_fungible(
+    client: &mut Client,
+    faucet_id: AccountId,
+    note_package: Arc<Package>,
+    recipient: AccountId,
+    data: Vec<u8>,
+) -> Result<(TransactionId, Note, NonFungibleAsset), DemoError> {
+    let asset = NonFungibleAsset::new(&NonFungibleAssetDetails::new(faucet_id.prefix(), data)?)?;
+
+    let inputs = NoteInputs::new(vec![recipient.prefix().as_felt(), recipient.suffix()])?;
+    let serial_num = client.rng().draw_word();
+    let note_recipient = NoteRecipient::new(serial_num, note_script(&note_package)?, inputs);
+    let config = AssetTransferConfig::default();
+
+    // The faucet procedure is called by MAST root, which the executor resolves from the
+    // faucet's account code
+    let mint_root = non_fungible_faucet_component()?.mint_root;
+    let source = format!(
+        "
+use.miden::tx
+
+begin
+    push.{recipient}
+    push.{execution_hint}
+    push.{note_type}
+    push.{aux}
+    push.{tag}
+    exec.tx::create_note
+    # => [note_idx]
+
+    push.{asset}
+    call.{mint_root}
+    dropw drop
+end
+",
+        recipient = masm_word(note_recipient.digest().into()),
+        execution_hint = Felt::from(config.execution_hint),
+        note_type = Felt::from(config.note_type),
+        aux = config.aux,
+        tag = Felt::from(config.tag),
+        asset = masm_word(Word::from(asset).into()),
+        mint_root = mint_root.to_hex(),
+    );
+    let script = TransactionScript::compile(source, TransactionKernel::assembler())
+        .map_err(|err| DemoError::ScriptCompilation(err.to_string()))?;
+
+    let request = TransactionRequestBuilder::new()
+        .custom_script(script)
+        .expected_output_recipients(vec![note_recipient.clone()])
+        .build()?;
+    let tx_result = client.new_transaction(faucet_id, request).await?;
+    let tx_id = tx_result.executed_transaction().id();
+    client.submit_transaction(tx_result).await?;
+
+    let metadata = NoteMetadata::new(
+        faucet_id,
+        config.note_type,
+        config.tag,
+        config.execution_hint,
+        config.aux,
+    )?;
+    let note = Note::new(
+        NoteAssets::new(vec![asset.into()])?,
+        metadata,
+        note_recipient,
+    );
+    Ok((tx_id, note, asset))
+}
+
+/// Formats a word as the operand of a MASM `push`
+fn masm_word(word: [Felt; 4]) -> String {
+    word.map(|felt| felt.to_string()).join(".")
+}
+
+/// Mints `amount` tokens to each recipient in a single faucet transaction
+///
+/// Every recipient gets a P2ID note built from `note_package`, in the order given. The notes
+/// are returned so the recipients can consume them.
+pub async fn mint_to_recipients(
+    client: &mut Client,
+    faucet_id: AccountId,
+    note_package: Arc<Package>,
+    recipients: &[(AccountId, u64)],
+) -> Result<(TransactionId, Vec<Note>), DemoError> {
+    let notes = recipients
+        .iter()
+        .map(|&(recipient, amount)| {
+            let asset = FungibleAsset::new(faucet_id, amount)?;
+            create_note_from_package(
+                client,
+                note_package.clone(),
+                faucet_id,
+                NoteCreationConfig {
+                    assets: NoteAssets::new(vec![asset.into()])?,
+                    inputs: vec![recipient.prefix().as_felt(), recipient.suffix()],
+                    ..Default::default()
+                },
+            )
+        })
+        .collect::<Result<Vec<_>, DemoError>>()?;
+
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(notes.iter().cloned().map(OutputNote::Full).collect())
+        .build()?;
+    let tx_result = client.new_transaction(faucet_id, request).await?;
+    let tx_id = tx_result.executed_transaction().id();
+    client.submit_transaction(tx_result).await?;
+
+    Ok((tx_id, notes))
+}
+
+/// Burns `amount` tokens held by `holder_id`
+///
+/// The holder moves the tokens into a burn note with the basic wallet transaction script, and
+/// the faucet consumes the note, which lowers its issuance. Both accounts have to be tracked by
+/// the client and their keys be in its keystore. Returns the holder's and the faucet's
+/// transaction IDs.
+pub async fn burn(
+    client: &mut Client,
+    holder_id: AccountId,
+    faucet_id: AccountId,
+    amount: u64,
+    tx_script_package: Arc<Package>,
+) -> Result<(TransactionId, TransactionId), DemoError> {
+    let script = NoteScript::compile(BURN_NOTE, TransactionKernel::assembler())?;
+    let serial_num = client.rng().draw_word();
+    let recipient = NoteRecipient::new(serial_num, script, NoteInputs::new(vec![])?);
+
+    let asset = FungibleAsset::new(faucet_id, amount)?;
+    let (send_tx_id, mut notes) = send_notes(
+        client,
+        holder_id,
+        vec![(recipient, vec![asset.into()])],
+        tx_script_package,
+        AssetTransferConfig::default(),
+    )
+    .await?;
+    let note = notes.remove(0);
+
+    let request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(note, None)])
+        .build()?;
+    let tx_result = client.new_transaction(faucet_id, request).await?;
+    let burn_tx_id = tx_result.executed_transaction().id();
+    client.submit_transaction(tx_result).await?;
+
+    Ok((send_tx_id, burn_tx_id))
+}
+
+/// Returns the issued and maximum supply of the fungible faucet `faucet_id`, as last synced
+pub async fn faucet_supply(
+    client: &mut Client,
+    faucet_id: AccountId,
+) -> Result<FaucetSupply, DemoError> {
+    let account: Account = client
+        .get_account(faucet_id)
+        .await?
+        .ok_or(DemoError::AccountNotFound(faucet_id))?
+        .into();
+    let issued = account.storage().get_item(FAUCET_SYSDATA_SLOT)?[3].as_int();
+    let faucet = BasicFungibleFaucet::try_from(account)
+        .map_err(|_| DemoError::NotAFungibleFaucet(faucet_id))?;
+
+    Ok(FaucetSupply {
+        decimals: faucet.decimals(),
+        issued,
+        max_supply: faucet.max_supply().as_int(),
+    })
+}
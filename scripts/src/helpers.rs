@@ -1,39 +1,92 @@
-//! Helper functions for counter contract deployment
+//! Helper functions for creating accounts and notes from compiled packages
 
-use std::sync::Arc;
+use std::{path::Path, sync::Arc};
 
+use miden_assembly::{Library, Program};
 use miden_client::{
     account::{
-        component::{BasicWallet, RpoFalcon512},
-        Account, AccountId, AccountStorageMode, AccountType, StorageSlot,
+        component::BasicWallet, Account, AccountId, AccountStorageMode, AccountType, StorageSlot,
     },
-    auth::AuthSecretKey,
-    crypto::{FeltRng, SecretKey},
-    keystore::FilesystemKeyStore,
+    crypto::FeltRng,
     note::{
         Note, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteScript, NoteTag,
         NoteType,
     },
-    Client, ClientError, Felt,
+    Client, Felt,
 };
-use miden_lib::utils::Deserializable;
-use miden_mast_package::Package;
+use miden_lib::utils::{Deserializable, Serializable};
+use miden_mast_package::{MastArtifact, Package};
 use miden_objects::{
     account::{
         AccountBuilder, AccountComponent, AccountComponentMetadata, AccountComponentTemplate,
     },
     FieldElement,
 };
-use rand::{rngs::StdRng, RngCore};
+use rand::RngCore;
 use std::collections::BTreeSet;
 
+use crate::{
+    auth::AuthScheme,
+    error::DemoError,
+    keystore::KeyStore,
+    package::PackageKind,
+    storage::{check_storage_layout, StorageInit},
+};
+
+/// Account types selectable on the command line
+///
+/// Only regular accounts; faucets are created with the `faucet` commands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum AccountTypeArg {
+    /// Regular account whose code can be updated
+    #[default]
+    RegularUpdatable,
+    /// Regular account whose code is fixed at creation
+    RegularImmutable,
+}
+
+impl From<AccountTypeArg> for AccountType {
+    fn from(account_type: AccountTypeArg) -> Self {
+        match account_type {
+            AccountTypeArg::RegularUpdatable => AccountType::RegularAccountUpdatableCode,
+            AccountTypeArg::RegularImmutable => AccountType::RegularAccountImmutableCode,
+        }
+    }
+}
+
+/// Account storage modes selectable on the command line
+///
+/// Network accounts are not supported by the helpers yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum StorageModeArg {
+    /// State is stored on chain
+    #[default]
+    Public,
+    /// Only a commitment is stored on chain; the state lives in the local store
+    Private,
+}
+
+impl From<StorageModeArg> for AccountStorageMode {
+    fn from(storage_mode: StorageModeArg) -> Self {
+        match storage_mode {
+            StorageModeArg::Public => AccountStorageMode::Public,
+            StorageModeArg::Private => AccountStorageMode::Private,
+        }
+    }
+}
+
 /// Configuration for creating an account with a custom component
 pub struct AccountCreationConfig {
     pub account_type: AccountType,
     pub storage_mode: AccountStorageMode,
     pub storage_slots: Vec<StorageSlot>,
+    /// Named initial storage, checked against the component's metadata; replaces
+    /// `storage_slots` when set
+    pub storage_init: Option<StorageInit>,
     pub supported_types: Option<Vec<AccountType>>,
     pub with_basic_wallet: bool,
+    /// Auth component of the account; its keys are added to the keystore
+    pub auth: AuthScheme,
 }
 
 impl Default for AccountCreationConfig {
@@ -42,65 +95,160 @@ impl Default for AccountCreationConfig {
             account_type: AccountType::RegularAccountUpdatableCode,
             storage_mode: AccountStorageMode::Public,
             storage_slots: vec![],
+            storage_init: None,
             supported_types: None,
             with_basic_wallet: true,
+            auth: AuthScheme::default(),
         }
     }
 }
 
+/// One Rust-compiled component of an account built by [`create_account_with_components`]
+pub struct ComponentConfig {
+    pub package: Arc<Package>,
+    pub storage_slots: Vec<StorageSlot>,
+    /// Named initial storage, checked against the component's metadata; replaces
+    /// `storage_slots` when set
+    pub storage_init: Option<StorageInit>,
+    /// Account types the component supports; defaults to the types declared in its metadata
+    pub supported_types: Option<Vec<AccountType>>,
+}
+
+impl ComponentConfig {
+    /// A component with no storage and the supported types from its metadata
+    pub fn new(package: Arc<Package>) -> Self {
+        Self {
+            package,
+            storage_slots: vec![],
+            storage_init: None,
+            supported_types: None,
+        }
+    }
+
+    /// Builds the component, checking its storage against the layout its metadata declares
+    fn build(self, account_type: AccountType) -> Result<AccountComponent, DemoError> {
+        let library = package_library(&self.package)?;
+        let metadata = component_metadata(&self.package)?;
+        let template = AccountComponentTemplate::new(metadata, library.as_ref().clone());
+
+        let component = match &self.storage_init {
+            Some(_) if !self.storage_slots.is_empty() => {
+                return Err(DemoError::InvalidStorageInit(
+                    "both storage slots and named initial storage were given".to_string(),
+                ))
+            }
+            Some(init) => init.build_component(&template)?,
+            None => {
+                check_storage_layout(template.metadata(), &self.storage_slots)?;
+                AccountComponent::new(template.library().clone(), self.storage_slots)?
+            }
+        };
+
+        // Use supported types from config if provided, otherwise the ones the component
+        // declares, falling back to RegularAccountUpdatableCode
+        let supported_types = match self.supported_types {
+            Some(types) => BTreeSet::from_iter(types),
+            None if !template.metadata().supported_types().is_empty() => {
+                template.metadata().supported_types().clone()
+            }
+            None => BTreeSet::from_iter([AccountType::RegularAccountUpdatableCode]),
+        };
+        if !supported_types.contains(&account_type) {
+            return Err(DemoError::UnsupportedAccountType {
+                component: self.package.name.clone(),
+                account_type: format!("{account_type:?}"),
+            });
+        }
+
+        Ok(component.with_supported_types(supported_types))
+    }
+}
+
 /// Helper to create an account with a custom component from a package
 pub async fn create_account_with_component(
     client: &mut Client,
-    keystore: Arc<FilesystemKeyStore<StdRng>>,
+    keystore: Arc<dyn KeyStore>,
     package: Arc<Package>,
+    mut config: AccountCreationConfig,
+) -> Result<Account, DemoError> {
+    let component = ComponentConfig {
+        package,
+        storage_slots: std::mem::take(&mut config.storage_slots),
+        storage_init: config.storage_init.take(),
+        supported_types: config.supported_types.take(),
+    };
+    create_account_with_components(client, keystore, vec![component], config).await
+}
+
+/// Helper to create an account from several Rust-compiled component packages
+///
+/// Components are added in order after the auth component and the optional `BasicWallet`.
+/// Each component's storage is checked against its metadata, and every component has to
+/// support `config.account_type`. The component fields of `config` (`storage_slots`,
+/// `storage_init` and `supported_types`) belong on the [`ComponentConfig`]s instead; setting
+/// one fails with [`DemoError::ComponentSettingOnAccountConfig`].
+pub async fn create_account_with_components(
+    client: &mut Client,
+    keystore: Arc<dyn KeyStore>,
+    components: Vec<ComponentConfig>,
     config: AccountCreationConfig,
-) -> Result<Account, ClientError> {
-    let account_component = match package.account_component_metadata_bytes.as_deref() {
-        None => panic!("no account component metadata present"),
-        Some(bytes) => {
-            let metadata = AccountComponentMetadata::read_from_bytes(bytes).unwrap();
-
-            let template =
-                AccountComponentTemplate::new(metadata, package.unwrap_library().as_ref().clone());
-
-            let component =
-                AccountComponent::new(template.library().clone(), config.storage_slots).unwrap();
-
-            // Use supported types from config if provided, otherwise default to RegularAccountUpdatableCode
-            let supported_types = if let Some(types) = config.supported_types {
-                BTreeSet::from_iter(types)
-            } else {
-                BTreeSet::from_iter([AccountType::RegularAccountUpdatableCode])
-            };
-
-            component.with_supported_types(supported_types)
+) -> Result<Account, DemoError> {
+    let component_setting = [
+        ("storage_slots", !config.storage_slots.is_empty()),
+        ("storage_init", config.storage_init.is_some()),
+        ("supported_types", config.supported_types.is_some()),
+    ]
+    .into_iter()
+    .find_map(|(field, set)| set.then_some(field));
+    if let Some(field) = component_setting {
+        return Err(DemoError::ComponentSettingOnAccountConfig(field));
+    }
+    let account_components = components
+        .into_iter()
+        .map(|component| component.build(config.account_type))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    build_and_register(client, keystore, config.auth, |builder| {
+        let mut builder = builder
+            .account_type(config.account_type)
+            .storage_mode(config.storage_mode);
+        if config.with_basic_wallet {
+            builder = builder.with_component(BasicWallet);
         }
-    };
+        account_components
+            .into_iter()
+            .fold(builder, |builder, component| {
+                builder.with_component(component)
+            })
+    })
+    .await
+}
 
+/// Builds an account and registers it with the client and its keys with the keystore
+///
+/// `configure` receives a builder with a fresh init seed and the auth component of `auth`, and
+/// adds everything else. The client is synced first so the account is anchored to the latest
+/// block, and the keys are only stored once the client tracks the account.
+pub(crate) async fn build_and_register(
+    client: &mut Client,
+    keystore: Arc<dyn KeyStore>,
+    auth: AuthScheme,
+    configure: impl FnOnce(AccountBuilder) -> AccountBuilder,
+) -> Result<Account, DemoError> {
     let mut init_seed = [0_u8; 32];
     client.rng().fill_bytes(&mut init_seed);
 
-    let key_pair = SecretKey::with_rng(client.rng());
+    let (auth_component, auth_keys) = auth.build(client.rng())?;
 
     // Sync client state to get latest block info
-    let _sync_summary = client.sync_state().await.unwrap();
-
-    let mut builder = AccountBuilder::new(init_seed)
-        .account_type(config.account_type)
-        .storage_mode(config.storage_mode)
-        .with_auth_component(RpoFalcon512::new(key_pair.public_key()));
-
-    if config.with_basic_wallet {
-        builder = builder.with_component(BasicWallet);
-    }
-
-    builder = builder.with_component(account_component);
+    let _sync_summary = client.sync_state().await?;
 
-    let (account, seed) = builder.build().unwrap();
+    let builder = AccountBuilder::new(init_seed).with_auth_component(auth_component);
+    let (account, seed) = configure(builder).build()?;
     client.add_account(&account, Some(seed), false).await?;
-    keystore
-        .add_key(&AuthSecretKey::RpoFalcon512(key_pair))
-        .unwrap();
+    for key in &auth_keys {
+        keystore.add_key(key)?;
+    }
 
     Ok(account)
 }
@@ -135,15 +283,11 @@ pub fn create_note_from_package(
     package: Arc<Package>,
     sender_id: AccountId,
     config: NoteCreationConfig,
-) -> Note {
-    let note_program = package.unwrap_program();
-    let note_script = NoteScript::from_parts(
-        note_program.mast_forest().clone(),
-        note_program.entrypoint(),
-    );
+) -> Result<Note, DemoError> {
+    let note_script = note_script(&package)?;
 
     let serial_num = client.rng().draw_word();
-    let note_inputs = NoteInputs::new(config.inputs).unwrap();
+    let note_inputs = NoteInputs::new(config.inputs)?;
     let recipient = NoteRecipient::new(serial_num, note_script, note_inputs);
 
     let metadata = NoteMetadata::new(
@@ -152,304 +296,62 @@ pub fn create_note_from_package(
         config.tag,
         config.execution_hint,
         config.aux,
-    )
-    .unwrap();
-
-    Note::new(config.assets, metadata, recipient)
-}
-
-/// Helper to compile a Rust package to Miden using the real compiler
-pub fn compile_rust_package(package_path: &str, release: bool) -> Arc<Package> {
-    use midenc_frontend_wasm::WasmTranslationConfig;
-
-    println!("  Compiling Rust package at: {}", package_path);
-
-    // Run the compilation in a blocking thread to avoid runtime conflicts
-    let package_path = package_path.to_string();
-    let handle = std::thread::spawn(move || {
-        // Use the exact same approach as CompilerTestBuilder::rust_source_cargo_miden
-        let config = WasmTranslationConfig::default();
-        let mut builder = CompilerTestBuilder::rust_source_cargo_miden(&package_path, config, []);
+    )?;
 
-        if release {
-            builder.with_release(true);
-        }
-
-        let mut test = builder.build();
-        test.compiled_package()
-    });
-
-    let package = handle.join().expect("Compilation thread panicked");
-    println!("  ✓ Successfully compiled package");
-    package
-}
-
-/// CompilerTestBuilder implementation copied from integration tests
-pub struct CompilerTestBuilder {
-    config: midenc_frontend_wasm::WasmTranslationConfig,
-    source: CompilerTestInputType,
-    link_masm_modules: Vec<(miden_assembly::LibraryPath, String)>,
-    midenc_flags: Vec<String>,
-    rustflags: Vec<std::borrow::Cow<'static, str>>,
-}
-
-pub enum CompilerTestInputType {
-    CargoMiden(CargoTest),
+    Ok(Note::new(config.assets, metadata, recipient))
 }
 
-pub struct CargoTest {
-    project_dir: std::path::PathBuf,
-    name: std::borrow::Cow<'static, str>,
-    release: bool,
-}
-
-impl CargoTest {
-    pub fn new(
-        name: impl Into<std::borrow::Cow<'static, str>>,
-        project_dir: std::path::PathBuf,
-    ) -> Self {
-        Self {
-            project_dir,
-            name: name.into(),
-            release: true,
-        }
+/// Returns the library of an account component package
+pub(crate) fn package_library(package: &Package) -> Result<Arc<Library>, DemoError> {
+    match &package.mast {
+        MastArtifact::Library(library) => Ok(library.clone()),
+        MastArtifact::Executable(_) => Err(DemoError::WrongPackageKind {
+            expected: PackageKind::Account,
+            actual: "program".to_string(),
+        }),
     }
 }
 
-impl CompilerTestBuilder {
-    pub fn new(source: CompilerTestInputType) -> Self {
-        let _name = match &source {
-            CompilerTestInputType::CargoMiden(config) => config.name.as_ref(),
-        };
-        let rustflags = vec![
-            "-C".into(),
-            "target-feature=+bulk-memory".into(),
-            "--remap-path-prefix".into(),
-            "../../=../../".into(),
-        ];
-        let midenc_flags = vec!["--verbose".into()];
-
-        Self {
-            config: Default::default(),
-            source,
-            link_masm_modules: vec![],
-            midenc_flags,
-            rustflags,
-        }
-    }
-
-    pub fn rust_source_cargo_miden(
-        cargo_project_folder: impl AsRef<std::path::Path>,
-        config: midenc_frontend_wasm::WasmTranslationConfig,
-        midenc_flags: impl IntoIterator<Item = String>,
-    ) -> Self {
-        let name = cargo_project_folder
-            .as_ref()
-            .file_stem()
-            .map(|name| name.to_string_lossy().into_owned())
-            .unwrap_or("".to_string());
-        let mut builder = CompilerTestBuilder::new(CompilerTestInputType::CargoMiden(
-            CargoTest::new(name, cargo_project_folder.as_ref().to_path_buf()),
-        ));
-        builder.config = config;
-        builder.midenc_flags.extend(midenc_flags);
-        builder
-    }
-
-    pub fn with_release(&mut self, release: bool) -> &mut Self {
-        match &mut self.source {
-            CompilerTestInputType::CargoMiden(config) => config.release = release,
-        }
-        self
-    }
-
-    pub fn build(mut self) -> CompilerTest {
-        use midenc_session::{InputFile, InputType};
-        use std::ffi::OsStr;
-        use std::process::{Command, Stdio};
-
-        // Set up the command used to compile the test inputs (Rust -> Wasm)
-        let mut command = Command::new("cargo");
-        command.arg("miden").arg("build");
-
-        // Extract the directory in which source code exists
-        let project_dir = match &self.source {
-            CompilerTestInputType::CargoMiden(config) => &config.project_dir,
-        };
-
-        // Cargo-based configuration
-        let CompilerTestInputType::CargoMiden(config) = &self.source;
-        {
-            let manifest_path = project_dir.join("Cargo.toml");
-            command.arg("--manifest-path").arg(manifest_path);
-            if config.release {
-                command.arg("--release");
-            }
-        }
-
-        // Set RUSTFLAGS
-        if !self.rustflags.is_empty() {
-            let mut flags = String::with_capacity(
-                self.rustflags.iter().map(|flag| flag.len()).sum::<usize>() + self.rustflags.len(),
-            );
-            for (i, flag) in self.rustflags.iter().enumerate() {
-                if i > 0 {
-                    flags.push(' ');
-                }
-                flags.push_str(flag.as_ref());
-            }
-            command.env("RUSTFLAGS", flags);
-        }
-
-        command.stdout(Stdio::piped());
-
-        // Build using cargo-miden
-        let mut args = vec![command.get_program().to_str().unwrap().to_string()];
-        let cmd_args: Vec<String> = command
-            .get_args()
-            .collect::<Vec<&OsStr>>()
-            .iter()
-            .map(|s| s.to_str().unwrap().to_string())
-            .collect();
-        args.extend(cmd_args);
-
-        let build_output = cargo_miden::run(args.into_iter(), cargo_miden::OutputType::Wasm)
-            .unwrap()
-            .expect("'cargo miden build' should return Some(CommandOutput)")
-            .unwrap_build_output();
-
-        let (wasm_artifact_path, mut extra_midenc_flags) = match build_output {
-            cargo_miden::BuildOutput::Wasm {
-                artifact_path,
-                midenc_flags,
-            } => (artifact_path, midenc_flags),
-            other => panic!("Expected Wasm output, got {:?}", other),
-        };
-
-        self.midenc_flags.append(&mut extra_midenc_flags);
-        let _artifact_name = wasm_artifact_path
-            .file_stem()
-            .unwrap()
-            .to_str()
-            .unwrap()
-            .to_string();
-        let input_file = InputFile::from_path(wasm_artifact_path).unwrap();
-        let mut inputs = vec![input_file];
-        inputs.extend(self.link_masm_modules.into_iter().map(|(path, content)| {
-            let path = path.to_string();
-            InputFile::new(
-                midenc_session::FileType::Masm,
-                InputType::Stdin {
-                    name: path.into(),
-                    input: content.into_bytes(),
-                },
-            )
-        }));
-
-        let context = default_context(inputs, &self.midenc_flags);
-        CompilerTest {
-            context,
-            hir: None,
-            masm_src: None,
-            ir_masm_program: None,
-            package: None,
-        }
-    }
-}
-
-pub struct CompilerTest {
-    pub context: std::rc::Rc<midenc_hir::Context>,
-    hir: Option<midenc_compile::LinkOutput>,
-    masm_src: Option<String>,
-    ir_masm_program: Option<Result<Arc<midenc_codegen_masm::MasmComponent>, String>>,
-    package: Option<Result<Arc<miden_mast_package::Package>, String>>,
+/// Decodes the account component metadata of an account component package
+pub(crate) fn component_metadata(package: &Package) -> Result<AccountComponentMetadata, DemoError> {
+    let bytes = package
+        .account_component_metadata_bytes
+        .as_deref()
+        .ok_or_else(|| DemoError::MissingComponentMetadata(package.name.clone()))?;
+    AccountComponentMetadata::read_from_bytes(bytes).map_err(DemoError::MetadataDecoding)
 }
 
-impl CompilerTest {
-    pub fn compiled_package(&mut self) -> Arc<miden_mast_package::Package> {
-        if self.package.is_none() {
-            self.compile_wasm_to_masm_program().unwrap();
-            // uncomment to print the MASM code
-            // println!("{}", self.masm_src.clone().unwrap());
-        }
-        match self.package.as_ref().unwrap().as_ref() {
-            Ok(prog) => prog.clone(),
-            Err(msg) => panic!("{msg}"),
-        }
-    }
-
-    fn link_output(&mut self) -> &midenc_compile::LinkOutput {
-        use midenc_compile::compile_to_optimized_hir;
-
-        if self.hir.is_none() {
-            let link_output = compile_to_optimized_hir(self.context.clone())
-                .map_err(format_report)
-                .expect("failed to translate wasm to hir component");
-            self.hir = Some(link_output);
-        }
-        self.hir.as_ref().unwrap()
-    }
-
-    fn compile_wasm_to_masm_program(&mut self) -> Result<(), String> {
-        use midenc_compile::{compile_link_output_to_masm_with_pre_assembly_stage, CodegenOutput};
-        use midenc_hir::Context;
-
-        let mut src = None;
-        let mut masm_program = None;
-        let mut stage = |output: CodegenOutput, _context: std::rc::Rc<Context>| {
-            src = Some(output.component.to_string());
-            if output.component.entrypoint.is_some() {
-                masm_program = Some(Arc::clone(&output.component));
-            }
-            Ok(output)
-        };
-
-        let link_output = self.link_output().clone();
-        let package = compile_link_output_to_masm_with_pre_assembly_stage(link_output, &mut stage)
-            .map_err(format_report)?
-            .unwrap_mast();
-
-        assert!(src.is_some(), "failed to pretty print masm artifact");
-        self.masm_src = src;
-        self.ir_masm_program = masm_program.map(Ok);
-        self.package = Some(Ok(Arc::new(package)));
-        Ok(())
+/// Returns the program of a note or transaction script package
+pub(crate) fn package_program(
+    package: &Package,
+    expected: PackageKind,
+) -> Result<Arc<Program>, DemoError> {
+    match &package.mast {
+        MastArtifact::Executable(program) => Ok(program.clone()),
+        MastArtifact::Library(_) => Err(DemoError::WrongPackageKind {
+            expected,
+            actual: "library".to_string(),
+        }),
     }
 }
 
-/// Create a valid [Context] for `inputs` with `argv`, with useful defaults.
-pub fn default_context<S, I>(inputs: I, argv: &[S]) -> std::rc::Rc<midenc_hir::Context>
-where
-    I: IntoIterator<Item = midenc_session::InputFile>,
-    S: AsRef<str>,
-{
-    let session = default_session(inputs, argv);
-    let context = std::rc::Rc::new(midenc_hir::Context::new(session));
-    midenc_codegen_masm::register_dialect_hooks(&context);
-    context
+/// Returns the note script of a `note-script` package
+pub(crate) fn note_script(package: &Package) -> Result<NoteScript, DemoError> {
+    let program = package_program(package, PackageKind::NoteScript)?;
+    Ok(NoteScript::from_parts(
+        program.mast_forest().clone(),
+        program.entrypoint(),
+    ))
 }
 
-/// Create a valid [Session] for compiling `inputs` with `argv`, with useful defaults.
-pub fn default_session<S, I>(inputs: I, argv: &[S]) -> std::rc::Rc<midenc_session::Session>
-where
-    I: IntoIterator<Item = midenc_session::InputFile>,
-    S: AsRef<str>,
-{
-    use midenc_session::diagnostics::reporting::{self, ReportHandlerOpts};
-
-    let result = reporting::set_hook(Box::new(|_| {
-        let wrapping_width = 300; // avoid wrapped file paths in the backtrace
-        Box::new(ReportHandlerOpts::new().width(wrapping_width).build())
-    }));
-    if result.is_ok() {
-        reporting::set_panic_hook();
-    }
-
-    let argv = argv.iter().map(|arg| arg.as_ref());
-    let session = midenc_compile::Compiler::new_session(inputs, None, argv);
-    std::rc::Rc::new(session)
+/// Writes a serialized note to `path`, so it can be consumed by a later invocation
+pub fn save_note(note: &Note, path: impl AsRef<Path>) -> Result<(), DemoError> {
+    std::fs::write(path, note.to_bytes())?;
+    Ok(())
 }
 
-fn format_report(err: impl std::fmt::Display) -> String {
-    format!("{}", err)
+/// Reads a note previously written with [`save_note`]
+pub fn load_note(path: impl AsRef<Path>) -> Result<Note, DemoError> {
+    let bytes = std::fs::read(path)?;
+    Note::read_from_bytes(&bytes).map_err(DemoError::NoteDecoding)
 }
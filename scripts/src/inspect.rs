@@ -0,0 +1,317 @@
+//! Account reports: vault contents with token metadata, nonce, code commitment and storage
+//!
+//! [`inspect_account`] collects everything into an [`AccountReport`], which prints as a table
+//! through `Display` or serializes to JSON.
+
+use std::fmt;
+
+use miden_client::{
+    account::{component::BasicFungibleFaucet, Account, AccountId, StorageSlot},
+    Client,
+};
+use miden_objects::asset::Asset;
+use serde::Serialize;
+
+use crate::{error::DemoError, network::Network, storage::format_word};
+
+/// Map slots with at most this many entries have them listed in the report
+pub const MAX_LISTED_MAP_ENTRIES: usize = 8;
+
+/// How a report is printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable table
+    #[default]
+    Table,
+    /// Pretty-printed JSON
+    Json,
+}
+
+/// State of an account as tracked by the local store
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountReport {
+    /// Bech32 account ID
+    pub id: String,
+    pub account_type: String,
+    pub storage_mode: String,
+    pub nonce: u64,
+    pub code_commitment: String,
+    /// Storage slots, in slot order
+    pub storage: Vec<SlotReport>,
+    /// Assets in the vault
+    pub assets: Vec<AssetReport>,
+}
+
+/// One storage slot
+#[derive(Debug, Clone, Serialize)]
+pub struct SlotReport {
+    pub index: u8,
+    /// `value` or `map`
+    pub kind: String,
+    /// The slot's word, or the root of a map slot
+    pub value: String,
+    /// Number of entries of a map slot
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entries: Option<usize>,
+    /// Entries of a map slot, if it has at most [`MAX_LISTED_MAP_ENTRIES`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub map_entries: Option<Vec<MapEntryReport>>,
+}
+
+/// One entry of a map slot
+#[derive(Debug, Clone, Serialize)]
+pub struct MapEntryReport {
+    pub key: String,
+    pub value: String,
+}
+
+/// One asset in a vault
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum AssetReport {
+    Fungible {
+        /// Bech32 ID of the issuing faucet
+        faucet_id: String,
+        /// Amount in base units
+        amount: u64,
+        /// Whether the local store tracks the faucet; token metadata is skipped otherwise
+        faucet_tracked: bool,
+        /// Token metadata, if the faucet is tracked by the local store
+        #[serde(skip_serializing_if = "Option::is_none")]
+        symbol: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        decimals: Option<u8>,
+    },
+    NonFungible {
+        /// Prefix of the issuing faucet's ID, the only part a non-fungible asset carries
+        faucet_id_prefix: String,
+        /// The asset's word, as `a,b,c,d`
+        asset: String,
+    },
+}
+
+/// Builds the report of `account_id` from the local store
+///
+/// Token metadata is read from faucets the store tracks; assets of other faucets are listed
+/// without it and marked as such. Map slots list their entries if they are few enough.
+pub async fn inspect_account(
+    client: &mut Client,
+    account_id: AccountId,
+    network: Network,
+) -> Result<AccountReport, DemoError> {
+    let account: Account = client
+        .get_account(account_id)
+        .await?
+        .ok_or(DemoError::AccountNotFound(account_id))?
+        .into();
+
+    let storage = account
+        .storage()
+        .slots()
+        .iter()
+        .enumerate()
+        .map(|(index, slot)| match slot {
+            StorageSlot::Value(value) => SlotReport {
+                index: index as u8,
+                kind: "value".to_string(),
+                value: value.to_hex(),
+                entries: None,
+                map_entries: None,
+            },
+            StorageSlot::Map(map) => SlotReport {
+                index: index as u8,
+                kind: "map".to_string(),
+                value: map.root().to_hex(),
+                entries: Some(map.entries().count()),
+                map_entries: (map.entries().count() <= MAX_LISTED_MAP_ENTRIES).then(|| {
+                    map.entries()
+                        .map(|(key, value)| MapEntryReport {
+                            key: key.to_hex(),
+                            value: value.to_hex(),
+                        })
+                        .collect()
+                }),
+            },
+        })
+        .collect();
+
+    let mut assets = vec![];
+    for asset in account.vault().assets() {
+        let report = match asset {
+            Asset::Fungible(asset) => {
+                let faucet_id = asset.faucet_id();
+                let record = client.get_account(faucet_id).await?;
+                let faucet_tracked = record.is_some();
+                let faucet = record
+                    .and_then(|record| BasicFungibleFaucet::try_from(Account::from(record)).ok());
+                AssetReport::Fungible {
+                    faucet_id: faucet_id.to_bech32(network.network_id()),
+                    amount: asset.amount(),
+                    faucet_tracked,
+                    symbol: faucet
+                        .as_ref()
+                        .and_then(|faucet| faucet.symbol().to_string().ok()),
+                    decimals: faucet.as_ref().map(|faucet| faucet.decimals()),
+                }
+            }
+            Asset::NonFungible(asset) => AssetReport::NonFungible {
+                faucet_id_prefix: asset.faucet_id_prefix().to_hex(),
+                asset: format_word(asset.into()),
+            },
+        };
+        assets.push(report);
+    }
+
+    Ok(AccountReport {
+        id: account.id().to_bech32(network.network_id()),
+        account_type: format!("{:?}", account.id().account_type()),
+        storage_mode: account.id().storage_mode().to_string(),
+        nonce: account.nonce().as_int(),
+        code_commitment: account.code().commitment().to_hex(),
+        storage,
+        assets,
+    })
+}
+
+impl AccountReport {
+    /// Renders the report in `format`
+    pub fn render(&self, format: OutputFormat) -> String {
+        match format {
+            OutputFormat::Table => self.to_string(),
+            OutputFormat::Json => {
+                serde_json::to_string_pretty(self).expect("account reports serialize to JSON")
+            }
+        }
+    }
+}
+
+impl fmt::Display for AccountReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Account {}", self.id)?;
+        writeln!(f, "  Type:            {}", self.account_type)?;
+        writeln!(f, "  Storage mode:    {}", self.storage_mode)?;
+        writeln!(f, "  Nonce:           {}", self.nonce)?;
+        writeln!(f, "  Code commitment: {}", self.code_commitment)?;
+
+        writeln!(f, "  Storage:")?;
+        writeln!(f, "    {:<5} {:<6} VALUE", "SLOT", "KIND")?;
+        for slot in &self.storage {
+            write!(f, "    {:<5} {:<6} {}", slot.index, slot.kind, slot.value)?;
+            match slot.entries {
+                Some(entries) => writeln!(f, " ({entries} entries)")?,
+                None => writeln!(f)?,
+            }
+            for entry in slot.map_entries.iter().flatten() {
+                writeln!(f, "          {} => {}", entry.key, entry.value)?;
+            }
+        }
+
+        writeln!(f, "  Vault:")?;
+        if self.assets.is_empty() {
+            return writeln!(f, "    (empty)");
+        }
+        writeln!(
+            f,
+            "    {:<12} {:<8} {:<20} FAUCET",
+            "KIND", "SYMBOL", "AMOUNT"
+        )?;
+        for asset in &self.assets {
+            match asset {
+                AssetReport::Fungible {
+                    faucet_id,
+                    amount,
+                    symbol,
+                    decimals,
+                    ..
+                } => writeln!(
+                    f,
+                    "    {:<12} {:<8} {:<20} {faucet_id}",
+                    "fungible",
+                    symbol.as_deref().unwrap_or("?"),
+                    format_amount(*amount, *decimals),
+                )?,
+                AssetReport::NonFungible {
+                    faucet_id_prefix,
+                    asset,
+                } => writeln!(
+                    f,
+                    "    {:<12} {:<8} {:<20} {faucet_id_prefix}",
+                    "non-fungible", "-", asset,
+                )?,
+            }
+        }
+
+        let untracked = self.assets.iter().any(|asset| {
+            matches!(
+                asset,
+                AssetReport::Fungible {
+                    faucet_tracked: false,
+                    ..
+                }
+            )
+        });
+        if untracked {
+            writeln!(
+                f,
+                "    Token metadata skipped for faucets not in the local store ('?')"
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats an amount in base units, with a decimal point if the token's decimals are known
+///
+/// Decimals come from faucet metadata, so more than a `u64` can scale by are possible; such
+/// amounts are printed in base units.
+fn format_amount(amount: u64, decimals: Option<u8>) -> String {
+    let scale = decimals
+        .filter(|&decimals| decimals > 0)
+        .and_then(|decimals| Some((decimals, 10u64.checked_pow(decimals as u32)?)));
+    match scale {
+        Some((decimals, scale)) => format!(
+            "{}.{:0width$}",
+            amount / scale,
+            amount % scale,
+            width = decimals as usize
+        ),
+        None => amount.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_amount;
+
+    #[test]
+    fn amounts_without_decimals_are_base_units() {
+        assert_eq!(format_amount(0, None), "0");
+        assert_eq!(format_amount(12_345, None), "12345");
+        assert_eq!(format_amount(12_345, Some(0)), "12345");
+    }
+
+    #[test]
+    fn amounts_are_split_at_the_decimal_point() {
+        assert_eq!(format_amount(12_345, Some(2)), "123.45");
+        assert_eq!(format_amount(100_000_000, Some(8)), "1.00000000");
+        assert_eq!(format_amount(0, Some(3)), "0.000");
+    }
+
+    #[test]
+    fn fractions_keep_leading_zeros() {
+        assert_eq!(format_amount(5, Some(8)), "0.00000005");
+        assert_eq!(format_amount(1_005, Some(3)), "1.005");
+    }
+
+    #[test]
+    fn large_amounts_and_decimals() {
+        assert_eq!(format_amount(u64::MAX, Some(12)), "18446744.073709551615");
+        assert_eq!(format_amount(u64::MAX, Some(19)), "1.8446744073709551615");
+    }
+
+    #[test]
+    fn decimals_beyond_u64_fall_back_to_base_units() {
+        assert_eq!(format_amount(12_345, Some(20)), "12345");
+        assert_eq!(format_amount(u64::MAX, Some(u8::MAX)), u64::MAX.to_string());
+    }
+}
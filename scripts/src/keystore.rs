@@ -0,0 +1,317 @@
+//! Keystores holding the secret keys of the accounts the helpers create
+//!
+//! The helpers only need to add a key after building an account and to look one up when
+//! signing, so both the plaintext `FilesystemKeyStore` from `miden-client` and the
+//! passphrase-protected [`EncryptedKeyStore`] sit behind the [`KeyStore`] trait.
+
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use argon2::Argon2;
+use chacha20poly1305::{
+    aead::{Aead, KeyInit},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use miden_client::{
+    account::AccountDelta,
+    auth::AuthSecretKey,
+    keystore::{FilesystemKeyStore, KeyStoreError},
+    Felt, Word,
+};
+use miden_lib::utils::{Deserializable, Serializable};
+use miden_tx::{
+    auth::{BasicAuthenticator, TransactionAuthenticator},
+    AuthenticationError,
+};
+use rand::rngs::StdRng;
+
+use crate::error::DemoError;
+
+/// Environment variable holding the passphrase of an encrypted keystore
+pub const KEYSTORE_PASSPHRASE_ENV: &str = "MIDEN_DEMO_KEYSTORE_PASSPHRASE";
+
+/// File in the keystore directory holding the salt the encryption key is derived with
+const SALT_FILE: &str = "salt";
+
+/// File in the keystore directory holding a known plaintext sealed under the keystore's key,
+/// used to check the passphrase when the keystore is opened
+const VERIFIER_FILE: &str = "verifier";
+
+/// Plaintext sealed in the verifier file
+const VERIFIER_PLAINTEXT: &[u8] = b"miden-demo encrypted keystore";
+
+/// Extension of encrypted key files
+const KEY_EXTENSION: &str = "key.enc";
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// The key-add and key-lookup interface the helpers use, on top of transaction signing
+pub trait KeyStore: TransactionAuthenticator + Send + Sync {
+    /// Stores `key`, indexed by its public key
+    fn add_key(&self, key: &AuthSecretKey) -> Result<(), KeyStoreError>;
+
+    /// Returns the secret key for `pub_key`, if the keystore holds it
+    fn get_key(&self, pub_key: Word) -> Result<Option<AuthSecretKey>, KeyStoreError>;
+}
+
+impl KeyStore for FilesystemKeyStore<StdRng> {
+    fn add_key(&self, key: &AuthSecretKey) -> Result<(), KeyStoreError> {
+        FilesystemKeyStore::add_key(self, key)
+    }
+
+    fn get_key(&self, pub_key: Word) -> Result<Option<AuthSecretKey>, KeyStoreError> {
+        FilesystemKeyStore::get_key(self, pub_key)
+    }
+}
+
+/// A filesystem keystore that encrypts every key with a passphrase
+///
+/// The encryption key is derived from the passphrase with Argon2 and a salt kept in the
+/// keystore directory. Each secret key is sealed with ChaCha20-Poly1305 under a fresh nonce
+/// and written to `<public key>.key.enc`. A known plaintext sealed the same way is kept next to
+/// the salt, so a wrong passphrase is rejected when the keystore is opened rather than when a
+/// key is first needed. On unix, all files are only readable by their owner.
+pub struct EncryptedKeyStore {
+    dir: PathBuf,
+    cipher: ChaCha20Poly1305,
+}
+
+impl EncryptedKeyStore {
+    /// Opens the keystore in `dir`, creating it if needed
+    ///
+    /// Fails with [`DemoError::WrongPassphrase`] if the keystore was created with a different
+    /// passphrase.
+    pub fn open(dir: impl Into<PathBuf>, passphrase: &str) -> Result<Self, DemoError> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+
+        let salt_path = dir.join(SALT_FILE);
+        let salt = if salt_path.exists() {
+            fs::read(&salt_path)?
+        } else {
+            let salt: [u8; SALT_LEN] = rand::random();
+            write_private(&salt_path, &salt)?;
+            salt.to_vec()
+        };
+
+        let mut key = Key::default();
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+            .map_err(|err| KeyStoreError::StorageError(format!("key derivation failed: {err}")))?;
+
+        let keystore = Self {
+            dir,
+            cipher: ChaCha20Poly1305::new(&key),
+        };
+        keystore.check_passphrase()?;
+        Ok(keystore)
+    }
+
+    /// Checks the derived key against the verifier, creating the verifier if there is none yet
+    ///
+    /// Keystores created before the verifier existed are checked against one of their keys
+    /// instead.
+    fn check_passphrase(&self) -> Result<(), DemoError> {
+        let verifier_path = self.dir.join(VERIFIER_FILE);
+        let matches = if verifier_path.exists() {
+            self.open_file(&verifier_path)?
+                .is_some_and(|plaintext| plaintext == VERIFIER_PLAINTEXT)
+        } else {
+            match self.any_key_file()? {
+                Some(path) => self.open_file(&path)?.is_some(),
+                None => true,
+            }
+        };
+        if !matches {
+            return Err(DemoError::WrongPassphrase(self.dir.clone()));
+        }
+        if !verifier_path.exists() {
+            write_private(&verifier_path, &self.seal(VERIFIER_PLAINTEXT)?)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the path of some key file in the keystore, if there is one
+    fn any_key_file(&self) -> Result<Option<PathBuf>, KeyStoreError> {
+        for entry in fs::read_dir(&self.dir).map_err(storage_error)? {
+            let path = entry.map_err(storage_error)?.path();
+            if path.to_string_lossy().ends_with(KEY_EXTENSION) {
+                return Ok(Some(path));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Encrypts `plaintext` under a fresh nonce, returning the nonce followed by the ciphertext
+    fn seal(&self, plaintext: &[u8]) -> Result<Vec<u8>, KeyStoreError> {
+        let nonce: [u8; NONCE_LEN] = rand::random();
+        let ciphertext = self
+            .cipher
+            .encrypt(Nonce::from_slice(&nonce), plaintext)
+            .map_err(|err| KeyStoreError::StorageError(format!("encryption failed: {err}")))?;
+
+        let mut contents = nonce.to_vec();
+        contents.extend(ciphertext);
+        Ok(contents)
+    }
+
+    /// Reads and decrypts a file written by [`seal`](Self::seal)
+    ///
+    /// Returns `None` if it does not decrypt, i.e. the passphrase is wrong or the file corrupt.
+    fn open_file(&self, path: &Path) -> Result<Option<Vec<u8>>, KeyStoreError> {
+        let contents = fs::read(path).map_err(storage_error)?;
+        if contents.len() < NONCE_LEN {
+            return Err(KeyStoreError::DecodingError(format!(
+                "{} is truncated",
+                path.display()
+            )));
+        }
+        let (nonce, ciphertext) = contents.split_at(NONCE_LEN);
+        Ok(self
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .ok())
+    }
+
+    fn key_path(&self, pub_key: Word) -> PathBuf {
+        self.dir
+            .join(format!("{}.{KEY_EXTENSION}", pub_key.to_hex()))
+    }
+}
+
+impl KeyStore for EncryptedKeyStore {
+    fn add_key(&self, key: &AuthSecretKey) -> Result<(), KeyStoreError> {
+        let pub_key = match key {
+            AuthSecretKey::RpoFalcon512(key) => Word::from(key.public_key()),
+        };
+        let contents = self.seal(key.to_bytes().as_slice())?;
+        write_private(&self.key_path(pub_key), &contents).map_err(storage_error)
+    }
+
+    fn get_key(&self, pub_key: Word) -> Result<Option<AuthSecretKey>, KeyStoreError> {
+        let path = self.key_path(pub_key);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let plaintext = self.open_file(&path)?.ok_or_else(|| {
+            KeyStoreError::DecodingError(format!(
+                "cannot decrypt {}: wrong passphrase or corrupt key",
+                path.display()
+            ))
+        })?;
+
+        AuthSecretKey::read_from_bytes(&plaintext)
+            .map(Some)
+            .map_err(|err| KeyStoreError::DecodingError(err.to_string()))
+    }
+}
+
+impl TransactionAuthenticator for EncryptedKeyStore {
+    fn get_signature(
+        &self,
+        pub_key: Word,
+        message: Word,
+        account_delta: &AccountDelta,
+    ) -> Result<Vec<Felt>, AuthenticationError> {
+        let key = KeyStore::get_key(self, pub_key)
+            .map_err(|err| AuthenticationError::other(err.to_string()))?
+            .ok_or_else(|| AuthenticationError::UnknownPublicKey(pub_key.to_hex()))?;
+
+        // Keys only exist in plaintext for the duration of a single signature
+        BasicAuthenticator::<StdRng>::new(&[(pub_key, key)]).get_signature(
+            pub_key,
+            message,
+            account_delta,
+        )
+    }
+}
+
+/// Returns whether `dir` holds an encrypted keystore
+pub fn is_encrypted_keystore(dir: impl AsRef<Path>) -> bool {
+    dir.as_ref().join(SALT_FILE).exists()
+}
+
+/// Writes `contents` to `path`, readable and writable only by the owner on unix
+fn write_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(path)?.write_all(contents)
+}
+
+fn storage_error(err: std::io::Error) -> KeyStoreError {
+    KeyStoreError::StorageError(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_client::{auth::AuthSecretKey, crypto::SecretKey, Word};
+    use miden_lib::utils::Serializable;
+
+    use super::{EncryptedKeyStore, KeyStore};
+    use crate::error::DemoError;
+
+    fn new_key() -> (Word, AuthSecretKey) {
+        let key = SecretKey::with_rng(&mut rand::rng());
+        (key.public_key().into(), AuthSecretKey::RpoFalcon512(key))
+    }
+
+    #[test]
+    fn keys_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let (pub_key, key) = new_key();
+
+        let keystore = EncryptedKeyStore::open(dir.path(), "correct horse").unwrap();
+        keystore.add_key(&key).unwrap();
+        drop(keystore);
+
+        let keystore = EncryptedKeyStore::open(dir.path(), "correct horse").unwrap();
+        let loaded = keystore.get_key(pub_key).unwrap().expect("key was added");
+        assert_eq!(loaded.to_bytes(), key.to_bytes());
+        assert!(keystore.get_key(new_key().0).unwrap().is_none());
+    }
+
+    #[test]
+    fn wrong_passphrase_is_rejected_on_open() {
+        let dir = tempfile::tempdir().unwrap();
+        EncryptedKeyStore::open(dir.path(), "correct horse").unwrap();
+
+        let result = EncryptedKeyStore::open(dir.path(), "battery staple");
+        assert!(matches!(result, Err(DemoError::WrongPassphrase(path)) if path == dir.path()));
+    }
+
+    #[test]
+    fn keystores_without_verifier_are_checked_against_a_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let keystore = EncryptedKeyStore::open(dir.path(), "correct horse").unwrap();
+        keystore.add_key(&new_key().1).unwrap();
+        std::fs::remove_file(dir.path().join(super::VERIFIER_FILE)).unwrap();
+
+        let result = EncryptedKeyStore::open(dir.path(), "battery staple");
+        assert!(matches!(result, Err(DemoError::WrongPassphrase(_))));
+        EncryptedKeyStore::open(dir.path(), "correct horse").unwrap();
+        assert!(dir.path().join(super::VERIFIER_FILE).exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn files_are_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let keystore = EncryptedKeyStore::open(dir.path(), "correct horse").unwrap();
+        keystore.add_key(&new_key().1).unwrap();
+
+        for entry in std::fs::read_dir(dir.path()).unwrap() {
+            let path = entry.unwrap().path();
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600, "{} is not private", path.display());
+        }
+    }
+}
@@ -0,0 +1,37 @@
+//! Helpers for compiling Miden Rust contracts and driving them with `miden-client`
+//!
+//! The `miden-demo` binary and the example flows in `src/bin/` are built on top of this crate,
+//! so other tools can depend on it instead of copying the helpers.
+
+pub mod auth;
+pub mod cache;
+pub mod compiler;
+pub mod counter;
+pub mod deployments;
+pub mod error;
+pub mod faucet;
+pub mod helpers;
+pub mod inspect;
+pub mod keystore;
+pub mod manifest;
+pub mod network;
+pub mod package;
+pub mod storage;
+pub mod tx_script;
+pub mod wallet;
+
+pub use auth::AuthScheme;
+pub use compiler::{compile_rust_package, compile_rust_packages, CompileOptions, CompiledPackage};
+pub use deployments::{AccountRef, AccountRequirements, Deployments};
+pub use error::DemoError;
+pub use helpers::{
+    create_account_with_component, create_account_with_components, create_note_from_package,
+    AccountCreationConfig, AccountTypeArg, ComponentConfig, NoteCreationConfig, StorageModeArg,
+};
+pub use keystore::{EncryptedKeyStore, KeyStore};
+pub use network::{build_client, ClientSettings, Network};
+pub use package::{
+    check_package_kind, load_or_compile_package, load_or_compile_package_of_kind, load_package,
+    package_path, save_package, PackageKind,
+};
+pub use storage::{ComponentStorage, StorageInit};
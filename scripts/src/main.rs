@@ -1,215 +1,987 @@
-//! Counter contract test module
+//! `miden-demo`: compile, deploy and interact with Miden Rust contracts
 
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Instant,
+};
+
+use clap::{Args, Parser, Subcommand};
+use miden_assembly::LibraryPath;
 use miden_client::{
-    account::StorageMap,
-    builder::ClientBuilder,
-    keystore::FilesystemKeyStore,
-    rpc::{Endpoint, TonicRpcClient},
+    account::{AccountStorageMode, AccountType, StorageMap, StorageSlot},
+    asset::{Asset, FungibleAsset, TokenSymbol},
     transaction::{OutputNote, TransactionRequestBuilder},
-    ClientError, Felt, Word,
+    Client, Felt, Word,
+};
+use miden_demo::{
+    check_package_kind, compile_rust_packages,
+    compiler::{compile_rust_package_with_emit, DebugInfo, EmitStage, OptLevel},
+    create_account_with_components, create_note_from_package,
+    deployments::DEFAULT_DEPLOYMENTS_PATH,
+    faucet::{
+        burn, create_fungible_faucet_account, create_non_fungible_faucet_account, faucet_supply,
+        is_fungible_faucet_for, mint_non_fungible, mint_to_recipients,
+    },
+    helpers::{load_note, save_note},
+    inspect::{inspect_account, OutputFormat},
+    load_or_compile_package,
+    network::{build_client, print_tx_link},
+    package::PACKAGE_EXTENSION,
+    save_package,
+    storage::{format_word, parse_word},
+    wallet::{send_asset_to_account, send_assets},
+    AccountCreationConfig, AccountRef, AccountRequirements, AccountTypeArg, AuthScheme,
+    ClientSettings, CompileOptions, CompiledPackage, ComponentConfig, DemoError, Deployments,
+    KeyStore, NoteCreationConfig, PackageKind, StorageInit, StorageModeArg,
 };
-use miden_objects::{account::NetworkId, FieldElement};
-use rand::prelude::StdRng;
-use std::sync::Arc;
+use miden_mast_package::Package;
 
-mod helpers;
+/// Compile, deploy and interact with Miden Rust contracts
+#[derive(Parser)]
+#[command(name = "miden-demo")]
+struct Cli {
+    #[command(flatten)]
+    client: ClientSettings,
 
-use helpers::{
-    compile_rust_package, create_account_with_component, create_note_from_package,
-    AccountCreationConfig, NoteCreationConfig,
-};
+    /// Registry of named accounts and notes, updated by every command that creates them
+    #[arg(long, global = true, default_value = DEFAULT_DEPLOYMENTS_PATH)]
+    deployments: PathBuf,
 
-fn assert_counter_storage(
-    counter_account_storage: &miden_client::account::AccountStorage,
-    expected: u64,
-) {
-    // according to `examples/counter-contract` for inner (slot, key) values
-    let counter_contract_storage_key = Word::from([Felt::ZERO, Felt::ZERO, Felt::ZERO, Felt::ONE]);
-
-    // The counter contract is in slot 1 when deployed, auth_component takes slot 0
-    let word = counter_account_storage
-        .get_map_item(1, counter_contract_storage_key)
-        .expect("Failed to get counter value from storage slot 1");
-
-    let val = word.last().unwrap();
-    assert_eq!(
-        val.as_int(),
-        expected,
-        "Counter value mismatch. Expected: {}, Got: {}",
-        expected,
-        val.as_int()
-    );
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Compile Rust contract crates into Miden packages
+    ///
+    /// Independent crates are compiled at the same time; Miden dependencies are built first.
+    Compile {
+        /// Paths to the contract crates
+        #[arg(required = true)]
+        packages: Vec<PathBuf>,
+        /// Write each compiled package to `<crate>.masp` in this directory
+        #[arg(long)]
+        out_dir: Option<PathBuf>,
+        /// Compiler stages to write to `<emit-dir>/<crate>/`; implies `--no-cache`
+        #[arg(long, value_enum, value_delimiter = ',')]
+        emit: Vec<EmitStage>,
+        /// Directory the `--emit` outputs are written to
+        #[arg(long, default_value = "target/miden")]
+        emit_dir: PathBuf,
+        #[command(flatten)]
+        build: BuildArgs,
+    },
+    /// Compile an account component and deploy an account with it
+    DeployAccount {
+        /// Path to the account component crate
+        package: PathBuf,
+        /// Name the account is registered under; defaults to the crate name
+        ///
+        /// If an account with this name was deployed from the same package and is still
+        /// tracked by the store, it is reused instead of deploying a new one.
+        #[arg(long)]
+        name: Option<String>,
+        /// Deploy a new account even if a registered one can be reused
+        #[arg(long)]
+        fresh: bool,
+        /// Do not add the built-in `BasicWallet` component
+        #[arg(long)]
+        no_basic_wallet: bool,
+        /// Account type; every component has to support it
+        #[arg(long, value_enum, default_value_t)]
+        account_type: AccountTypeArg,
+        /// Storage mode; the state of a private account only exists in the local store
+        #[arg(long, value_enum, default_value_t)]
+        storage_mode: StorageModeArg,
+        /// Auth scheme: `rpo-falcon512`, `none` or `multisig:<signers>`
+        #[arg(long, default_value_t)]
+        auth: AuthScheme,
+        /// Initial storage map entry for the component, as `k0,k1,k2,k3=v0,v1,v2,v3`
+        #[arg(long = "map-entry", value_parser = parse_map_entry, conflicts_with = "storage")]
+        map_entries: Vec<(Word, Word)>,
+        /// TOML file with the component's initial storage, keyed by slot name
+        ///
+        /// The values are checked against the storage the component declares before the
+        /// account is built.
+        #[arg(long)]
+        storage: Option<PathBuf>,
+        /// Additional component crate, as `<path>` or `<path>=<storage.toml>`; may be repeated
+        ///
+        /// Components are added after the main one, in the order given.
+        #[arg(long = "component", value_parser = parse_component)]
+        components: Vec<(PathBuf, Option<PathBuf>)>,
+        #[command(flatten)]
+        build: BuildArgs,
+    },
+    /// Compile a note script, create the note from an account and save it to a file
+    CreateNote {
+        /// Path to the note script crate
+        package: PathBuf,
+        /// Account that creates the note, by ID or registered name
+        #[arg(long)]
+        sender: AccountRef,
+        /// Name the note is registered under
+        #[arg(long)]
+        name: Option<String>,
+        /// Note input felt; may be repeated
+        #[arg(long = "input")]
+        inputs: Vec<u64>,
+        /// File the created note is written to
+        #[arg(long, default_value = "note.bin")]
+        out: PathBuf,
+        #[command(flatten)]
+        build: BuildArgs,
+    },
+    /// Consume a note saved by `create-note` or `transfer`
+    ConsumeNote {
+        /// Account that consumes the note, by ID or registered name
+        #[arg(long)]
+        account: AccountRef,
+        /// File the note was written to
+        #[arg(long, default_value = "note.bin")]
+        note: PathBuf,
+    },
+    /// Send fungible tokens to another account through a P2ID note
+    Transfer {
+        /// Sending account, by ID or registered name
+        #[arg(long)]
+        sender: AccountRef,
+        /// Receiving account, by ID or registered name
+        #[arg(long)]
+        recipient: AccountRef,
+        /// Faucet that issued the tokens, by ID or registered name
+        #[arg(long)]
+        faucet: AccountRef,
+        #[arg(long)]
+        amount: u64,
+        /// Path to the P2ID note crate
+        #[arg(long, default_value = "../p2id-note")]
+        note_package: PathBuf,
+        /// Path to the basic wallet transaction script crate
+        #[arg(long, default_value = "../basic-wallet-tx-script")]
+        tx_script_package: PathBuf,
+        /// File the recipient's note is written to
+        #[arg(long, default_value = "note.bin")]
+        out: PathBuf,
+        #[command(flatten)]
+        build: BuildArgs,
+    },
+    /// Send assets to several accounts in one transaction, one P2ID note per recipient
+    TransferBatch {
+        /// Sending account, by ID or registered name
+        #[arg(long)]
+        sender: AccountRef,
+        /// Fungible transfer as `<recipient>=<amount>@<faucet>`, accounts by ID or registered
+        /// name; may be repeated, and transfers to the same recipient share a note
+        #[arg(long = "to", value_parser = parse_transfer, required_unless_present = "assets")]
+        transfers: Vec<(AccountRef, u64, AccountRef)>,
+        /// Transfer of any asset, e.g. a non-fungible one, as `<recipient>=<a,b,c,d>` with the
+        /// asset's word; may be repeated
+        #[arg(long = "asset", value_parser = parse_asset_transfer)]
+        assets: Vec<(AccountRef, Word)>,
+        /// Path to the P2ID note crate
+        #[arg(long, default_value = "../p2id-note")]
+        note_package: PathBuf,
+        /// Path to the basic wallet transaction script crate
+        #[arg(long, default_value = "../basic-wallet-tx-script")]
+        tx_script_package: PathBuf,
+        /// Directory the notes are written to, as `transfer-<n>.bin` in recipient order
+        #[arg(long, default_value = ".")]
+        out_dir: PathBuf,
+        #[command(flatten)]
+        build: BuildArgs,
+    },
+    /// Print an account's vault with token metadata, nonce, code commitment and storage
+    Inspect {
+        /// Account ID or registered name
+        account: AccountRef,
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
+    },
+    /// Create faucets, mint and burn their assets, and report fungible supply
+    Faucet {
+        #[command(subcommand)]
+        command: FaucetCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum FaucetCommand {
+    /// Create a basic fungible faucet
+    Create {
+        /// Name the faucet is registered under
+        #[arg(long, default_value = "faucet")]
+        name: String,
+        /// Token symbol, up to 6 uppercase letters
+        #[arg(long, value_parser = parse_token_symbol)]
+        symbol: TokenSymbol,
+        #[arg(long, default_value_t = 8)]
+        decimals: u8,
+        /// Maximum number of base units that can be in circulation
+        #[arg(long)]
+        max_supply: u64,
+        /// Auth scheme: `rpo-falcon512`, `none` or `multisig:<signers>`
+        #[arg(long, default_value_t)]
+        auth: AuthScheme,
+        /// Create a new faucet even if a registered one can be reused
+        #[arg(long)]
+        fresh: bool,
+    },
+    /// Mint tokens to one or more accounts through P2ID notes
+    Mint {
+        /// Faucet, by ID or registered name
+        #[arg(long)]
+        faucet: AccountRef,
+        /// Recipient and amount, as `<account>=<amount>`; may be repeated
+        #[arg(long = "to", value_parser = parse_mint_recipient, required = true)]
+        recipients: Vec<(AccountRef, u64)>,
+        /// Path to the P2ID note crate
+        #[arg(long, default_value = "../p2id-note")]
+        note_package: PathBuf,
+        /// Directory the notes are written to, as `mint-<n>.bin` in recipient order
+        #[arg(long, default_value = ".")]
+        out_dir: PathBuf,
+        #[command(flatten)]
+        build: BuildArgs,
+    },
+    /// Burn tokens held by an account; the faucet has to be tracked by the local store
+    Burn {
+        /// Faucet that issued the tokens, by ID or registered name
+        #[arg(long)]
+        faucet: AccountRef,
+        /// Account holding the tokens, by ID or registered name
+        #[arg(long)]
+        holder: AccountRef,
+        #[arg(long)]
+        amount: u64,
+        /// Path to the basic wallet transaction script crate
+        #[arg(long, default_value = "../basic-wallet-tx-script")]
+        tx_script_package: PathBuf,
+        #[command(flatten)]
+        build: BuildArgs,
+    },
+    /// Create a non-fungible faucet
+    CreateNft {
+        /// Name the faucet is registered under
+        #[arg(long, default_value = "nft-faucet")]
+        name: String,
+        /// Auth scheme: `rpo-falcon512`, `none` or `multisig:<signers>`
+        #[arg(long, default_value_t)]
+        auth: AuthScheme,
+        /// Create a new faucet even if a registered one can be reused
+        #[arg(long)]
+        fresh: bool,
+    },
+    /// Mint a non-fungible asset to an account through a P2ID note
+    MintNft {
+        /// Non-fungible faucet, by ID or registered name
+        #[arg(long)]
+        faucet: AccountRef,
+        /// Recipient, by ID or registered name
+        #[arg(long)]
+        to: AccountRef,
+        /// Data the asset is derived from; different data gives a different asset
+        #[arg(long)]
+        data: String,
+        /// Path to the P2ID note crate
+        #[arg(long, default_value = "../p2id-note")]
+        note_package: PathBuf,
+        /// File the note is written to
+        #[arg(long, default_value = "nft-note.bin")]
+        out: PathBuf,
+        #[command(flatten)]
+        build: BuildArgs,
+    },
+    /// Print the issued and maximum supply of a faucet
+    Supply {
+        /// Faucet, by ID or registered name
+        faucet: AccountRef,
+    },
+}
+
+/// Options shared by every command that compiles a crate
+///
+/// Package paths may point at a contract crate or at a prebuilt `.masp` file.
+#[derive(Args)]
+struct BuildArgs {
+    /// Build in debug mode instead of release
+    #[arg(long)]
+    debug: bool,
+    /// Always recompile, ignoring the package cache
+    #[arg(long)]
+    no_cache: bool,
+    /// Extra flag passed to midenc; may be repeated
+    #[arg(long = "midenc-flag", allow_hyphen_values = true)]
+    midenc_flags: Vec<String>,
+    /// Extra flag added to `RUSTFLAGS`; may be repeated
+    #[arg(long = "rustflag", allow_hyphen_values = true)]
+    rustflags: Vec<String>,
+    /// Debug info level
+    #[arg(long, value_enum)]
+    debug_info: Option<DebugInfo>,
+    /// Optimization level
+    #[arg(long, value_enum)]
+    opt_level: Option<OptLevel>,
+    /// Hand-written MASM module to link, as `<module path>=<file.masm>`; may be repeated
+    #[arg(long = "link-masm", value_parser = parse_masm_module)]
+    masm_modules: Vec<(LibraryPath, PathBuf)>,
+}
+
+impl BuildArgs {
+    fn options(&self) -> Result<CompileOptions, DemoError> {
+        let mut options = CompileOptions {
+            release: !self.debug,
+            debug_info: self.debug_info,
+            opt_level: self.opt_level,
+            no_cache: self.no_cache,
+            ..Default::default()
+        };
+        options
+            .midenc_flags
+            .extend(self.midenc_flags.iter().cloned());
+        options.rustflags.extend(self.rustflags.iter().cloned());
+        for (path, file) in &self.masm_modules {
+            options.link_masm_file(path.clone(), file)?;
+        }
+        Ok(options)
+    }
+
+    fn compile(&self, package_path: &Path) -> Result<Arc<Package>, DemoError> {
+        load_or_compile_package(package_path, &self.options()?)
+    }
+
+    /// Compiles or loads `package_path`, failing unless it is of the `expected` kind
+    fn compile_kind(
+        &self,
+        package_path: &Path,
+        expected: PackageKind,
+    ) -> Result<Arc<Package>, DemoError> {
+        let package = self.compile(package_path)?;
+        check_package_kind(package_path, &package, expected)?;
+        Ok(package)
+    }
 }
 
-/// Tests the counter contract deployment and note consumption workflow.
 #[tokio::main]
-async fn main() -> Result<(), ClientError> {
-    println!("=== Miden Counter Contract Deployment and Note Consumption ===");
-    println!("This script demonstrates the full workflow of:");
-    println!("1. Compiling Rust packages to Miden");
-    println!("2. Creating accounts with counter contract components");
-    println!("3. Creating and consuming counter notes");
-    println!("4. Verifying counter incrementation");
-    println!();
-
-    // Initialize client & keystore
-    let endpoint = Endpoint::testnet();
-    let timeout_ms = 10_000;
-    let rpc_api = Arc::new(TonicRpcClient::new(&endpoint, timeout_ms));
-
-    let mut client = ClientBuilder::new()
-        .rpc(rpc_api)
-        .filesystem_keystore("./keystore")
-        .in_debug_mode(true)
-        .build()
-        .await?;
-
-    let sync_summary = client.sync_state().await.unwrap();
-    println!("✓ Connected to Miden testnet");
-    println!("  Latest block: {}", sync_summary.block_num);
-
-    let keystore: FilesystemKeyStore<StdRng> =
-        FilesystemKeyStore::new("./keystore".into()).unwrap();
-
-    // Compile the contracts first (before creating any runtime)
-    println!("\n[STEP 1] Compiling Rust packages...");
-    let contract_package = compile_rust_package("../counter-contract", true);
-    let note_package = compile_rust_package("../counter-contract-note", true);
-    println!("✓ Compiled counter contract package");
-    println!("✓ Compiled counter note package");
-
-    // Create the counter account with initial storage
-    println!("\n[STEP 2] Creating counter account with initial storage...");
-    let key = Word::from([Felt::ZERO, Felt::ZERO, Felt::ZERO, Felt::ONE]);
-    let value = Word::from([Felt::ZERO, Felt::ZERO, Felt::ZERO, Felt::ONE]);
-    let config = AccountCreationConfig {
-        storage_slots: vec![miden_client::account::StorageSlot::Map(
-            StorageMap::with_entries([(key.into(), value)]).unwrap(),
-        )],
-        ..Default::default()
-    };
-
-    let counter_account = create_account_with_component(
-        &mut client,
-        Arc::new(keystore.clone()),
-        contract_package,
-        config,
-    )
-    .await
-    .unwrap();
-    println!("✓ Counter account created successfully!");
-    println!(
-        "  Account ID: {}",
-        counter_account.id().to_bech32(NetworkId::Testnet)
-    );
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+    let network = cli.client.network;
+    let mut deployments = Deployments::load(&cli.deployments)?;
 
-    // The counter contract storage value should be 1 after the account creation
-    assert_counter_storage(
-        client
-            .get_account(counter_account.id())
-            .await
-            .unwrap()
-            .unwrap()
-            .account()
-            .storage(),
-        1,
-    );
-    println!("✓ Initial counter value verified: 1");
-
-    // Create the counter note from sender to counter
-    println!("\n[STEP 3] Creating counter note...");
-    let counter_note = create_note_from_package(
-        &mut client,
-        note_package,
-        counter_account.id(),
-        NoteCreationConfig::default(),
-    );
-    println!("✓ Counter note created");
-    println!("  Note hash: {:?}", counter_note.id().to_hex());
-
-    // Submit transaction to create the note
-    println!("\n[STEP 4] Submitting transaction to create the note...");
-    let note_request = TransactionRequestBuilder::new()
-        .own_output_notes(vec![OutputNote::Full(counter_note.clone())])
-        .build()
-        .unwrap();
-
-    let tx_result = client
-        .new_transaction(counter_account.id(), note_request)
-        .await
-        .map_err(|e| {
-            eprintln!("Transaction creation error: {e}");
-            e
-        })
-        .unwrap();
-    let executed_transaction = tx_result.executed_transaction();
-
-    assert_eq!(executed_transaction.output_notes().num_notes(), 1);
-
-    let executed_tx_output_note = executed_transaction.output_notes().get_note(0);
-    assert_eq!(executed_tx_output_note.id(), counter_note.id());
-    let create_note_tx_id = executed_transaction.id();
-    client.submit_transaction(tx_result).await.unwrap();
-    println!("✓ Counter note creation transaction submitted");
-    println!(
-        "  View on MidenScan: https://testnet.midenscan.com/tx/{:?}",
-        create_note_tx_id
-    );
+    match cli.command {
+        Command::Compile {
+            packages,
+            out_dir,
+            emit,
+            emit_dir,
+            build,
+        } => {
+            let options = build.options()?;
+            let compiled = if emit.is_empty() {
+                compile_rust_packages(&packages, &options)?
+            } else {
+                // Emitting intermediate stages always bypasses the cache
+                packages
+                    .iter()
+                    .map(|path| {
+                        let start = Instant::now();
+                        let package = compile_rust_package_with_emit(
+                            &path.to_string_lossy(),
+                            &options,
+                            &emit,
+                            &emit_dir,
+                        )?;
+                        Ok(CompiledPackage {
+                            path: path.clone(),
+                            package,
+                            elapsed: start.elapsed(),
+                        })
+                    })
+                    .collect::<Result<Vec<_>, DemoError>>()?
+            };
+
+            for CompiledPackage {
+                path,
+                package,
+                elapsed,
+            } in compiled
+            {
+                println!("✓ Compiled package '{}' in {:.2?}", package.name, elapsed);
+                println!("  Digest: {}", package.digest());
+                if let Some(out_dir) = &out_dir {
+                    let file_name = path.file_stem().unwrap_or(path.as_os_str());
+                    let out = out_dir.join(file_name).with_extension(PACKAGE_EXTENSION);
+                    save_package(&out, &package)?;
+                    println!("  Saved to {}", out.display());
+                }
+            }
+        }
+        Command::DeployAccount {
+            package,
+            name,
+            fresh,
+            no_basic_wallet,
+            account_type,
+            storage_mode,
+            auth,
+            map_entries,
+            storage,
+            components,
+            build,
+        } => {
+            let name = name.unwrap_or_else(|| crate_name(&package));
+            let package = build.compile_kind(&package, PackageKind::Account)?;
+            let extra_components = components
+                .iter()
+                .map(|(path, storage)| {
+                    let mut component =
+                        ComponentConfig::new(build.compile_kind(path, PackageKind::Account)?);
+                    component.storage_init = storage.as_ref().map(StorageInit::read).transpose()?;
+                    Ok(component)
+                })
+                .collect::<Result<Vec<_>, DemoError>>()?;
+            let packages: Vec<Arc<Package>> = std::iter::once(package.clone())
+                .chain(
+                    extra_components
+                        .iter()
+                        .map(|component| component.package.clone()),
+                )
+                .collect();
+            let package_refs: Vec<&Package> = packages.iter().map(AsRef::as_ref).collect();
+            let (mut client, keystore) = connect(&cli.client).await?;
+
+            let requirements = AccountRequirements {
+                packages: &package_refs,
+                auth,
+                account_type: account_type.into(),
+                storage_mode: storage_mode.into(),
+            };
+            if let Some(account) = deployments
+                .reusable_account(
+                    &mut client,
+                    keystore.as_ref(),
+                    network,
+                    &name,
+                    &requirements,
+                    fresh,
+                )
+                .await?
+            {
+                println!("✓ Reusing account '{name}'");
+                println!(
+                    "  Account ID: {}",
+                    account.id().to_bech32(network.network_id())
+                );
+                return Ok(());
+            }
+
+            let mut storage_slots = vec![];
+            if !map_entries.is_empty() {
+                let entries = map_entries
+                    .into_iter()
+                    .map(|(key, value)| (key.into(), value));
+                storage_slots.push(StorageSlot::Map(StorageMap::with_entries(entries)?));
+            }
+            let main_component = ComponentConfig {
+                storage_slots,
+                storage_init: storage.map(StorageInit::read).transpose()?,
+                ..ComponentConfig::new(package)
+            };
+            let config = AccountCreationConfig {
+                account_type: account_type.into(),
+                storage_mode: storage_mode.into(),
+                with_basic_wallet: !no_basic_wallet,
+                auth,
+                ..Default::default()
+            };
+
+            let account = create_account_with_components(
+                &mut client,
+                keystore,
+                std::iter::once(main_component)
+                    .chain(extra_components)
+                    .collect(),
+                config,
+            )
+            .await?;
+            deployments.record_account(network, &name, &account, &package_refs, &auth);
+            deployments.save()?;
+            println!("✓ Account '{name}' created");
+            println!(
+                "  Account ID: {}",
+                account.id().to_bech32(network.network_id())
+            );
+        }
+        Command::CreateNote {
+            package,
+            sender,
+            name,
+            inputs,
+            out,
+            build,
+        } => {
+            let package = build.compile_kind(&package, PackageKind::NoteScript)?;
+            let sender = deployments.resolve(network, &sender)?;
+            let (mut client, _) = connect(&cli.client).await?;
+
+            let note = create_note_from_package(
+                &mut client,
+                package.clone(),
+                sender,
+                NoteCreationConfig {
+                    inputs: inputs.into_iter().map(Felt::new).collect(),
+                    ..Default::default()
+                },
+            )?;
+            let request = TransactionRequestBuilder::new()
+                .own_output_notes(vec![OutputNote::Full(note.clone())])
+                .build()?;
+            let tx_result = client.new_transaction(sender, request).await?;
+            let tx_id = tx_result.executed_transaction().id();
+            client.submit_transaction(tx_result).await?;
+
+            if let Some(name) = &name {
+                deployments.record_note(network, name, &note, Some(package.as_ref()));
+            }
+            deployments.record_account_transaction(network, sender, tx_id);
+            deployments.record_note_transaction(network, note.id(), tx_id);
+            deployments.save()?;
+
+            save_note(&note, &out)?;
+            println!("✓ Note created and saved to {}", out.display());
+            println!("  Note ID: {}", note.id().to_hex());
+            print_tx_link(network, tx_id);
+        }
+        Command::ConsumeNote { account, note } => {
+            let (mut client, _) = connect(&cli.client).await?;
+            let account = deployments.resolve(network, &account)?;
+            let note = load_note(&note)?;
+            let note_id = note.id();
+
+            let request = TransactionRequestBuilder::new()
+                .unauthenticated_input_notes([(note, None)])
+                .build()?;
+            let tx_result = client.new_transaction(account, request).await?;
+            let tx_id = tx_result.executed_transaction().id();
+            client.submit_transaction(tx_result).await?;
+
+            deployments.record_account_transaction(network, account, tx_id);
+            deployments.record_note_transaction(network, note_id, tx_id);
+            deployments.save()?;
+
+            println!("✓ Note consumed");
+            print_tx_link(network, tx_id);
+        }
+        Command::Transfer {
+            sender,
+            recipient,
+            faucet,
+            amount,
+            note_package,
+            tx_script_package,
+            out,
+            build,
+        } => {
+            let note_package = build.compile_kind(&note_package, PackageKind::NoteScript)?;
+            let tx_script_package =
+                build.compile_kind(&tx_script_package, PackageKind::TransactionScript)?;
+            let (mut client, _) = connect(&cli.client).await?;
+
+            let sender = deployments.resolve(network, &sender)?;
+            let recipient = deployments.resolve(network, &recipient)?;
+            let faucet = deployments.resolve(network, &faucet)?;
+            let asset = FungibleAsset::new(faucet, amount)?;
+            let (tx_id, note) = send_asset_to_account(
+                &mut client,
+                sender,
+                recipient,
+                asset,
+                note_package.clone(),
+                tx_script_package,
+                None,
+            )
+            .await?;
+            deployments.record_account_transaction(network, sender, tx_id);
+            deployments.record_note(network, note_name(&out), &note, Some(note_package.as_ref()));
+            deployments.record_note_transaction(network, note.id(), tx_id);
+            deployments.save()?;
+
+            save_note(&note, &out)?;
+            println!(
+                "✓ Sent {amount} tokens; recipient note saved to {}",
+                out.display()
+            );
+            print_tx_link(network, tx_id);
+        }
+        Command::TransferBatch {
+            sender,
+            transfers,
+            assets,
+            note_package,
+            tx_script_package,
+            out_dir,
+            build,
+        } => {
+            let note_package = build.compile_kind(&note_package, PackageKind::NoteScript)?;
+            let tx_script_package =
+                build.compile_kind(&tx_script_package, PackageKind::TransactionScript)?;
+            let (mut client, _) = connect(&cli.client).await?;
 
-    // Consume the note to increment the counter
-    println!("\n[STEP 5] Consuming the note to increment the counter...");
-    let consume_request = TransactionRequestBuilder::new()
-        .unauthenticated_input_notes([(counter_note, None)])
-        .build()
-        .unwrap();
-
-    let tx_result = client
-        .new_transaction(counter_account.id(), consume_request)
-        .await
-        .map_err(|e| {
-            eprintln!("Note consumption transaction error: {e}");
-            e
-        })
-        .unwrap();
-    let consume_tx_id = tx_result.executed_transaction().id();
-    println!("✓ Counter note consumption transaction created");
+            let sender = deployments.resolve(network, &sender)?;
+            let transfers = transfers
+                .iter()
+                .map(|(recipient, amount, faucet)| {
+                    let recipient = deployments.resolve(network, recipient)?;
+                    let faucet = deployments.resolve(network, faucet)?;
+                    Ok((recipient, FungibleAsset::new(faucet, *amount)?.into()))
+                })
+                .chain(assets.iter().map(|(recipient, asset)| {
+                    let recipient = deployments.resolve(network, recipient)?;
+                    Ok((recipient, Asset::try_from(*asset)?))
+                }))
+                .collect::<Result<Vec<_>, DemoError>>()?;
+            let (tx_id, notes) = send_assets(
+                &mut client,
+                sender,
+                &transfers,
+                note_package.clone(),
+                tx_script_package,
+                None,
+            )
+            .await?;
+            deployments.record_account_transaction(network, sender, tx_id);
+            for (index, note) in notes.iter().enumerate() {
+                let name = format!("transfer-{index}");
+                deployments.record_note(network, name, note, Some(note_package.as_ref()));
+                deployments.record_note_transaction(network, note.id(), tx_id);
+            }
+            deployments.save()?;
+
+            for (index, note) in notes.iter().enumerate() {
+                let out = out_dir.join(format!("transfer-{index}.bin"));
+                save_note(note, &out)?;
+                println!(
+                    "✓ Sent {} assets in note {}; saved to {}",
+                    note.assets().num_assets(),
+                    note.id().to_hex(),
+                    out.display()
+                );
+            }
+            print_tx_link(network, tx_id);
+        }
+        Command::Inspect { account, format } => {
+            let account = deployments.resolve(network, &account)?;
+            let (mut client, _) = connect(&cli.client).await?;
+            let report = inspect_account(&mut client, account, network).await?;
+            println!("{}", report.render(format));
+        }
+        Command::Faucet { command } => {
+            run_faucet_command(command, &cli.client, &mut deployments).await?
+        }
+    }
+
+    Ok(())
+}
+
+/// Builds and syncs a client, and opens the keystore it uses
+async fn connect(settings: &ClientSettings) -> Result<(Client, Arc<dyn KeyStore>), DemoError> {
+    let (mut client, keystore) = build_client(settings).await?;
+    let sync_summary = client.sync_state().await?;
     println!(
-        "  View on MidenScan: https://testnet.midenscan.com/tx/{:?}",
-        consume_tx_id
+        "✓ Connected to Miden {} at block {}",
+        settings.network, sync_summary.block_num
     );
+    Ok((client, keystore))
+}
 
-    client.submit_transaction(tx_result).await.unwrap();
-    println!("✓ Counter note consumption transaction submitted");
-
-    // Sync state to get latest updates
-    println!("\n[STEP 6] Syncing state and verifying counter incrementation...");
-    let sync_result = client.sync_state().await.unwrap();
-    println!("✓ Synced to block: {}", sync_result.block_num);
-
-    // The counter contract storage value should be 2 (incremented) after the note is consumed
-    assert_counter_storage(
-        client
-            .get_account(counter_account.id())
-            .await
-            .unwrap()
-            .unwrap()
-            .account()
-            .storage(),
-        2,
-    );
-    println!("✓ Counter value after incrementation verified: 2");
-
-    // Final summary
-    println!("\n=== SUCCESS: Counter Contract Workflow Completed! ===");
-    println!();
-    println!("✓ Compiled Rust packages to Miden");
-    println!("✓ Created counter account with initial storage (value: 1)");
-    println!("✓ Created and submitted counter note");
-    println!("✓ Consumed counter note to increment counter");
-    println!("✓ Verified counter incrementation (value: 1 → 2)");
-    println!();
-    println!("The complete counter contract deployment and note consumption");
-    println!("workflow has been successfully demonstrated using the new Rust compiler!");
+/// Runs a `faucet` subcommand
+async fn run_faucet_command(
+    command: FaucetCommand,
+    settings: &ClientSettings,
+    deployments: &mut Deployments,
+) -> Result<(), DemoError> {
+    let network = settings.network;
+    match command {
+        FaucetCommand::Create {
+            name,
+            symbol,
+            decimals,
+            max_supply,
+            auth,
+            fresh,
+        } => {
+            let (mut client, keystore) = connect(settings).await?;
+            let requirements = AccountRequirements {
+                packages: &[],
+                auth,
+                account_type: AccountType::FungibleFaucet,
+                storage_mode: AccountStorageMode::Public,
+            };
+            if let Some(account) = deployments
+                .reusable_account(
+                    &mut client,
+                    keystore.as_ref(),
+                    network,
+                    &name,
+                    &requirements,
+                    fresh,
+                )
+                .await?
+                .filter(|account| {
+                    is_fungible_faucet_for(account, symbol, decimals, Felt::new(max_supply))
+                })
+            {
+                println!("✓ Reusing faucet '{name}'");
+                println!(
+                    "  Faucet ID: {}",
+                    account.id().to_bech32(network.network_id())
+                );
+                return Ok(());
+            }
+
+            let account = create_fungible_faucet_account(
+                &mut client,
+                keystore,
+                symbol,
+                decimals,
+                Felt::new(max_supply),
+                auth,
+            )
+            .await?;
+            deployments.record_account(network, &name, &account, &[], &auth);
+            deployments.save()?;
+            println!("✓ Faucet '{name}' created");
+            println!(
+                "  Faucet ID: {}",
+                account.id().to_bech32(network.network_id())
+            );
+        }
+        FaucetCommand::Mint {
+            faucet,
+            recipients,
+            note_package,
+            out_dir,
+            build,
+        } => {
+            let note_package = build.compile_kind(&note_package, PackageKind::NoteScript)?;
+            let faucet = deployments.resolve(network, &faucet)?;
+            let recipients = recipients
+                .iter()
+                .map(|(account, amount)| Ok((deployments.resolve(network, account)?, *amount)))
+                .collect::<Result<Vec<_>, DemoError>>()?;
+            let (mut client, _) = connect(settings).await?;
+
+            let (tx_id, notes) =
+                mint_to_recipients(&mut client, faucet, note_package.clone(), &recipients).await?;
+            deployments.record_account_transaction(network, faucet, tx_id);
+            for (index, note) in notes.iter().enumerate() {
+                let name = format!("mint-{index}");
+                deployments.record_note(network, name, note, Some(note_package.as_ref()));
+                deployments.record_note_transaction(network, note.id(), tx_id);
+            }
+            deployments.save()?;
 
+            for (index, (note, (recipient, amount))) in notes.iter().zip(&recipients).enumerate() {
+                let out = out_dir.join(format!("mint-{index}.bin"));
+                save_note(note, &out)?;
+                println!(
+                    "✓ Minted {amount} to {}; note saved to {}",
+                    recipient.to_bech32(network.network_id()),
+                    out.display()
+                );
+            }
+            print_tx_link(network, tx_id);
+        }
+        FaucetCommand::Burn {
+            faucet,
+            holder,
+            amount,
+            tx_script_package,
+            build,
+        } => {
+            let tx_script_package =
+                build.compile_kind(&tx_script_package, PackageKind::TransactionScript)?;
+            let faucet = deployments.resolve(network, &faucet)?;
+            let holder = deployments.resolve(network, &holder)?;
+            let (mut client, _) = connect(settings).await?;
+
+            let (send_tx_id, burn_tx_id) =
+                burn(&mut client, holder, faucet, amount, tx_script_package).await?;
+            deployments.record_account_transaction(network, holder, send_tx_id);
+            deployments.record_account_transaction(network, faucet, burn_tx_id);
+            deployments.save()?;
+
+            println!("✓ Burned {amount} tokens");
+            print_tx_link(network, send_tx_id);
+            print_tx_link(network, burn_tx_id);
+        }
+        FaucetCommand::CreateNft { name, auth, fresh } => {
+            let (mut client, keystore) = connect(settings).await?;
+            let requirements = AccountRequirements {
+                packages: &[],
+                auth,
+                account_type: AccountType::NonFungibleFaucet,
+                storage_mode: AccountStorageMode::Public,
+            };
+            if let Some(account) = deployments
+                .reusable_account(
+                    &mut client,
+                    keystore.as_ref(),
+                    network,
+                    &name,
+                    &requirements,
+                    fresh,
+                )
+                .await?
+            {
+                println!("✓ Reusing faucet '{name}'");
+                println!(
+                    "  Faucet ID: {}",
+                    account.id().to_bech32(network.network_id())
+                );
+                return Ok(());
+            }
+
+            let account = create_non_fungible_faucet_account(&mut client, keystore, auth).await?;
+            deployments.record_account(network, &name, &account, &[], &auth);
+            deployments.save()?;
+            println!("✓ Non-fungible faucet '{name}' created");
+            println!(
+                "  Faucet ID: {}",
+                account.id().to_bech32(network.network_id())
+            );
+        }
+        FaucetCommand::MintNft {
+            faucet,
+            to,
+            data,
+            note_package,
+            out,
+            build,
+        } => {
+            let note_package = build.compile_kind(&note_package, PackageKind::NoteScript)?;
+            let faucet = deployments.resolve(network, &faucet)?;
+            let recipient = deployments.resolve(network, &to)?;
+            let (mut client, _) = connect(settings).await?;
+
+            let (tx_id, note, asset) = mint_non_fungible(
+                &mut client,
+                faucet,
+                note_package.clone(),
+                recipient,
+                data.into_bytes(),
+            )
+            .await?;
+            deployments.record_account_transaction(network, faucet, tx_id);
+            deployments.record_note(network, note_name(&out), &note, Some(note_package.as_ref()));
+            deployments.record_note_transaction(network, note.id(), tx_id);
+            deployments.save()?;
+
+            save_note(&note, &out)?;
+            println!(
+                "✓ Minted non-fungible asset to {}; note saved to {}",
+                recipient.to_bech32(network.network_id()),
+                out.display()
+            );
+            println!("  Asset: {}", format_word(asset.into()));
+            print_tx_link(network, tx_id);
+        }
+        FaucetCommand::Supply { faucet } => {
+            let faucet = deployments.resolve(network, &faucet)?;
+            let (mut client, _) = connect(settings).await?;
+            let supply = faucet_supply(&mut client, faucet).await?;
+
+            println!("Faucet {}", faucet.to_bech32(network.network_id()));
+            println!("  Decimals: {}", supply.decimals);
+            println!("  Issued: {}", supply.issued);
+            println!("  Max supply: {}", supply.max_supply);
+            println!(
+                "  Remaining: {}",
+                supply.max_supply.saturating_sub(supply.issued)
+            );
+        }
+    }
     Ok(())
 }
+
+/// Returns the name a note saved to `out` is registered under: the file name without extension
+fn note_name(out: &Path) -> String {
+    out.file_stem()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| out.display().to_string())
+}
+
+/// Returns the crate or package file name of `package_path`
+fn crate_name(package_path: &Path) -> String {
+    package_path
+        .file_stem()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| package_path.to_string_lossy().into_owned())
+}
+
+/// Parses a `<module path>=<file>` MASM module to link
+fn parse_masm_module(s: &str) -> Result<(LibraryPath, PathBuf), String> {
+    let (path, file) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected MODULE=FILE, got '{s}'"))?;
+    let path =
+        LibraryPath::new(path).map_err(|err| format!("invalid module path '{path}': {err}"))?;
+    Ok((path, PathBuf::from(file)))
+}
+
+/// Parses a `<path>[=<storage.toml>]` component
+fn parse_component(s: &str) -> Result<(PathBuf, Option<PathBuf>), String> {
+    Ok(match s.split_once('=') {
+        Some((path, storage)) => (PathBuf::from(path), Some(PathBuf::from(storage))),
+        None => (PathBuf::from(s), None),
+    })
+}
+
+/// Parses a `<account>=<amount>` mint recipient
+fn parse_mint_recipient(s: &str) -> Result<(AccountRef, u64), String> {
+    let (account, amount) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected ACCOUNT=AMOUNT, got '{s}'"))?;
+    let amount = amount
+        .parse()
+        .map_err(|err| format!("invalid amount '{amount}': {err}"))?;
+    Ok((account.parse()?, amount))
+}
+
+/// Parses a `<recipient>=<amount>@<faucet>` transfer
+fn parse_transfer(s: &str) -> Result<(AccountRef, u64, AccountRef), String> {
+    let (recipient, asset) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected RECIPIENT=AMOUNT@FAUCET, got '{s}'"))?;
+    let (amount, faucet) = asset
+        .split_once('@')
+        .ok_or_else(|| format!("expected RECIPIENT=AMOUNT@FAUCET, got '{s}'"))?;
+    let amount = amount
+        .parse()
+        .map_err(|err| format!("invalid amount '{amount}': {err}"))?;
+    Ok((recipient.parse()?, amount, faucet.parse()?))
+}
+
+/// Parses a `<recipient>=<a,b,c,d>` asset transfer
+fn parse_asset_transfer(s: &str) -> Result<(AccountRef, Word), String> {
+    let (recipient, asset) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected RECIPIENT=ASSET, got '{s}'"))?;
+    Ok((recipient.parse()?, parse_word(asset)?))
+}
+
+/// Parses a token symbol
+fn parse_token_symbol(s: &str) -> Result<TokenSymbol, String> {
+    TokenSymbol::new(s).map_err(|err| format!("invalid token symbol '{s}': {err}"))
+}
+
+/// Parses a `key=value` storage map entry
+fn parse_map_entry(s: &str) -> Result<(Word, Word), String> {
+    let (key, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected KEY=VALUE, got '{s}'"))?;
+    Ok((parse_word(key)?, parse_word(value)?))
+}
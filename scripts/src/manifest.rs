@@ -0,0 +1,71 @@
+//! The Miden-specific parts of a contract crate's `Cargo.toml`
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::error::DemoError;
+
+/// Miden metadata read from a contract crate's `Cargo.toml`
+#[derive(Debug, Clone, Default)]
+pub struct MidenManifest {
+    /// `[package.metadata.miden] project-kind`
+    pub project_kind: Option<String>,
+    /// Crates listed in `[package.metadata.miden.dependencies]`, relative to the working directory
+    pub dependencies: Vec<PathBuf>,
+    /// WIT files listed in `[package.metadata.component.target.dependencies]`
+    pub wit_dependencies: Vec<PathBuf>,
+}
+
+impl MidenManifest {
+    /// Reads the manifest of the crate at `package_path`
+    pub fn read(package_path: impl AsRef<Path>) -> Result<Self, DemoError> {
+        let package_path = package_path.as_ref();
+        let manifest_path = package_path.join("Cargo.toml");
+        let manifest = fs::read_to_string(&manifest_path)?;
+        let manifest: toml::Table =
+            toml::from_str(&manifest).map_err(|err| DemoError::InvalidManifest {
+                path: manifest_path,
+                message: err.to_string(),
+            })?;
+
+        let metadata = manifest
+            .get("package")
+            .and_then(|package| package.get("metadata"));
+        let miden = metadata.and_then(|metadata| metadata.get("miden"));
+
+        let project_kind = miden
+            .and_then(|miden| miden.get("project-kind"))
+            .and_then(|kind| kind.as_str())
+            .map(str::to_string);
+        let dependencies = dependency_paths(
+            package_path,
+            miden.and_then(|miden| miden.get("dependencies")),
+        );
+        let wit_dependencies = dependency_paths(
+            package_path,
+            metadata
+                .and_then(|metadata| metadata.get("component"))
+                .and_then(|component| component.get("target"))
+                .and_then(|target| target.get("dependencies")),
+        );
+
+        Ok(Self {
+            project_kind,
+            dependencies,
+            wit_dependencies,
+        })
+    }
+}
+
+/// Collects the `path` entries of a dependency table, resolved against `package_path`
+fn dependency_paths(package_path: &Path, table: Option<&toml::Value>) -> Vec<PathBuf> {
+    table
+        .and_then(|table| table.as_table())
+        .into_iter()
+        .flat_map(|table| table.values())
+        .filter_map(|dependency| dependency.get("path").and_then(|path| path.as_str()))
+        .map(|path| package_path.join(path))
+        .collect()
+}
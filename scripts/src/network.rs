@@ -0,0 +1,226 @@
+//! Network selection for the deployment scripts
+
+use std::{
+    fmt,
+    path::PathBuf,
+    sync::{Arc, OnceLock},
+};
+
+use miden_client::{
+    builder::ClientBuilder,
+    crypto::RpoRandomCoin,
+    keystore::FilesystemKeyStore,
+    rpc::{Endpoint, NodeRpcClient, TonicRpcClient},
+    testing::mock::MockRpcApi,
+    transaction::TransactionId,
+    Client, Felt, Word,
+};
+use miden_objects::account::NetworkId;
+use tempfile::TempDir;
+
+use crate::{
+    error::DemoError,
+    keystore::{is_encrypted_keystore, EncryptedKeyStore, KeyStore, KEYSTORE_PASSPHRASE_ENV},
+};
+
+/// The network the scripts talk to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum Network {
+    /// In-process mock chain from the `miden-client` `testing` feature; no network needed
+    Mock,
+    /// A node running on localhost
+    Local,
+    /// The public Miden testnet
+    #[default]
+    Testnet,
+}
+
+impl Network {
+    /// Network ID used when rendering bech32 account IDs
+    pub fn network_id(&self) -> NetworkId {
+        match self {
+            Network::Testnet => NetworkId::Testnet,
+            Network::Mock | Network::Local => NetworkId::Devnet,
+        }
+    }
+
+    /// Returns a MidenScan link for the transaction, if the network has an explorer
+    pub fn explorer_url(&self, tx_id: TransactionId) -> Option<String> {
+        match self {
+            Network::Testnet => Some(format!("https://testnet.midenscan.com/tx/{:?}", tx_id)),
+            Network::Mock | Network::Local => None,
+        }
+    }
+
+    /// Default path of the sqlite store for this network
+    ///
+    /// The mock chain lives only as long as the process, so it has no default path; it gets a
+    /// temporary store instead (see [`ClientSettings::store_path`]).
+    pub fn default_store_path(&self) -> Option<PathBuf> {
+        match self {
+            Network::Mock => None,
+            Network::Local => Some(PathBuf::from("./store-local.sqlite3")),
+            Network::Testnet => Some(PathBuf::from("./store.sqlite3")),
+        }
+    }
+
+    fn rpc_api(&self) -> Arc<dyn NodeRpcClient + Send> {
+        let timeout_ms = 10_000;
+        match self {
+            Network::Mock => Arc::new(MockRpcApi::default()),
+            Network::Local => Arc::new(TonicRpcClient::new(&Endpoint::localhost(), timeout_ms)),
+            Network::Testnet => Arc::new(TonicRpcClient::new(&Endpoint::testnet(), timeout_ms)),
+        }
+    }
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Network::Mock => write!(f, "mock"),
+            Network::Local => write!(f, "local"),
+            Network::Testnet => write!(f, "testnet"),
+        }
+    }
+}
+
+/// Returns the deterministic RNG used by clients built with `seed`
+pub fn seeded_rng(seed: u64) -> RpoRandomCoin {
+    RpoRandomCoin::new(Word::from([
+        Felt::new(seed),
+        Felt::new(0),
+        Felt::new(0),
+        Felt::new(0),
+    ]))
+}
+
+/// Where a client connects to and keeps its state
+///
+/// Derives `clap::Args` so every binary accepts the same `--network`, `--keystore`, `--store`,
+/// `--encrypted-keystore` and `--seed` options.
+#[derive(Debug, Clone, clap::Args)]
+pub struct ClientSettings {
+    /// Network to run against
+    ///
+    /// The mock chain only lives for a single invocation, so commands that depend on
+    /// earlier ones need `local` or `testnet`.
+    #[arg(long, value_enum, global = true, default_value_t = Network::Testnet)]
+    pub network: Network,
+
+    /// Directory of the keystore
+    #[arg(long, global = true, default_value = "./keystore")]
+    pub keystore: PathBuf,
+
+    /// Encrypt the keystore with the passphrase in `$MIDEN_DEMO_KEYSTORE_PASSPHRASE`
+    #[arg(long, global = true)]
+    pub encrypted_keystore: bool,
+
+    /// Path of the sqlite store; defaults to a per-network file in the working directory
+    #[arg(long, global = true)]
+    pub store: Option<PathBuf>,
+
+    /// Seed the client RNG so runs against a fresh store are reproducible
+    ///
+    /// Only allowed with the mock network or a store that does not exist yet.
+    #[arg(long, global = true)]
+    pub seed: Option<u64>,
+
+    /// Temporary directory of the mock store, deleted when the last clone of the settings is
+    /// dropped
+    #[arg(skip)]
+    mock_store: Arc<OnceLock<TempDir>>,
+}
+
+impl ClientSettings {
+    /// Default settings for `network`
+    pub fn new(network: Network) -> Self {
+        Self {
+            network,
+            keystore: PathBuf::from("./keystore"),
+            encrypted_keystore: false,
+            store: None,
+            seed: None,
+            mock_store: Arc::default(),
+        }
+    }
+
+    /// Path of the sqlite store
+    ///
+    /// Without `--store`, the mock network gets a store in a temporary directory that lives as
+    /// long as these settings.
+    pub fn store_path(&self) -> Result<PathBuf, DemoError> {
+        if let Some(path) = self
+            .store
+            .clone()
+            .or_else(|| self.network.default_store_path())
+        {
+            return Ok(path);
+        }
+        if self.mock_store.get().is_none() {
+            let dir = tempfile::Builder::new()
+                .prefix("miden-demo-mock-")
+                .tempdir()?;
+            let _ = self.mock_store.set(dir);
+        }
+        let dir = self
+            .mock_store
+            .get()
+            .expect("mock store directory was just created");
+        Ok(dir.path().join("store.sqlite3"))
+    }
+
+    /// Opens the keystore, encrypted with the passphrase from the environment if requested
+    pub fn open_keystore(&self) -> Result<Arc<dyn KeyStore>, DemoError> {
+        if self.encrypted_keystore {
+            let passphrase = std::env::var(KEYSTORE_PASSPHRASE_ENV)
+                .map_err(|_| DemoError::MissingPassphrase(KEYSTORE_PASSPHRASE_ENV))?;
+            Ok(Arc::new(EncryptedKeyStore::open(
+                &self.keystore,
+                &passphrase,
+            )?))
+        } else if is_encrypted_keystore(&self.keystore) {
+            // Never mix plaintext keys into an encrypted keystore
+            Err(DemoError::EncryptedKeyStore(self.keystore.clone()))
+        } else {
+            Ok(Arc::new(FilesystemKeyStore::<rand::rngs::StdRng>::new(
+                self.keystore.clone(),
+            )?))
+        }
+    }
+}
+
+/// Builds a client from `settings`, and returns it with the keystore it signs with
+///
+/// With a seed, the client RNG is [`seeded_rng`] instead of an OS-seeded one. The helpers
+/// draw account init seeds, Falcon keys and note serial numbers from the client RNG, so runs
+/// with the same seed against a fresh store produce the same accounts, notes and transactions.
+/// A seed is rejected for an existing store on a real network: the accounts in it would be
+/// reused while the RNG redraws serial numbers of notes that were already consumed.
+pub async fn build_client(
+    settings: &ClientSettings,
+) -> Result<(Client, Arc<dyn KeyStore>), DemoError> {
+    let store_path = settings.store_path()?;
+    if settings.seed.is_some() && settings.network != Network::Mock && store_path.exists() {
+        return Err(DemoError::SeededStoreNotFresh(store_path));
+    }
+
+    let keystore = settings.open_keystore()?;
+    let mut builder = ClientBuilder::new()
+        .rpc(settings.network.rpc_api())
+        .sqlite_store(&store_path.to_string_lossy())
+        .authenticator(keystore.clone())
+        .in_debug_mode(true);
+    if let Some(seed) = settings.seed {
+        builder = builder.rng(Box::new(seeded_rng(seed)));
+    }
+    let client = builder.build().await?;
+    Ok((client, keystore))
+}
+
+/// Prints the explorer link for a transaction, if the network has one
+pub fn print_tx_link(network: Network, tx_id: TransactionId) {
+    match network.explorer_url(tx_id) {
+        Some(url) => println!("  View on MidenScan: {}", url),
+        None => println!("  Transaction ID: {:?}", tx_id),
+    }
+}
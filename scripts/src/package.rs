@@ -0,0 +1,153 @@
+//! Reading and writing compiled `.masp` packages
+
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
+
+use miden_lib::utils::{Deserializable, Serializable};
+use miden_mast_package::{MastArtifact, Package};
+
+use crate::{
+    compiler::{compile_rust_package, CompileOptions},
+    error::DemoError,
+    manifest::MidenManifest,
+};
+
+/// File extension of serialized packages
+pub const PACKAGE_EXTENSION: &str = "masp";
+
+/// What a package is used for, as declared by `project-kind` in the crate's `Cargo.toml`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackageKind {
+    /// An account component library
+    Account,
+    /// A note script program
+    NoteScript,
+    /// A transaction script program
+    TransactionScript,
+}
+
+impl PackageKind {
+    /// The `project-kind` value of this kind
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PackageKind::Account => "account",
+            PackageKind::NoteScript => "note-script",
+            PackageKind::TransactionScript => "transaction-script",
+        }
+    }
+
+    /// Whether packages of this kind are compiled to a library rather than a program
+    fn is_library(&self) -> bool {
+        matches!(self, PackageKind::Account)
+    }
+}
+
+impl fmt::Display for PackageKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for PackageKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "account" => Ok(PackageKind::Account),
+            "note-script" => Ok(PackageKind::NoteScript),
+            "transaction-script" => Ok(PackageKind::TransactionScript),
+            _ => Err(format!("unknown project kind '{s}'")),
+        }
+    }
+}
+
+/// Checks that the package built from or loaded at `path` is of the `expected` kind
+///
+/// Crates are checked against the `project-kind` in their manifest. Prebuilt `.masp` files do
+/// not record it, so for those only the shape of the MAST is checked: account packages are
+/// libraries, note and transaction scripts are programs.
+pub fn check_package_kind(
+    path: impl AsRef<Path>,
+    package: &Package,
+    expected: PackageKind,
+) -> Result<(), DemoError> {
+    let path = path.as_ref();
+    if path.is_dir() {
+        if let Some(actual) = MidenManifest::read(path)?.project_kind {
+            if actual != expected.as_str() {
+                return Err(DemoError::WrongPackageKind { expected, actual });
+            }
+        }
+    }
+
+    let actual = match &package.mast {
+        MastArtifact::Library(_) if !expected.is_library() => "library",
+        MastArtifact::Executable(_) if expected.is_library() => "program",
+        _ => return Ok(()),
+    };
+    Err(DemoError::WrongPackageKind {
+        expected,
+        actual: actual.to_string(),
+    })
+}
+
+/// Loads a package serialized with [`save_package`]
+pub fn load_package(path: impl AsRef<Path>) -> Result<Arc<Package>, DemoError> {
+    let bytes = fs::read(path)?;
+    let package = Package::read_from_bytes(&bytes).map_err(DemoError::PackageDecoding)?;
+    Ok(Arc::new(package))
+}
+
+/// Serializes `package` to `path`, creating parent directories as needed
+pub fn save_package(path: impl AsRef<Path>, package: &Package) -> Result<(), DemoError> {
+    let path = path.as_ref();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, package.to_bytes())?;
+    Ok(())
+}
+
+/// Returns where the package of the contract crate `crate_name` is loaded or compiled from
+///
+/// That is `<package_dir>/<crate_name>.masp` if a directory of prebuilt packages was given,
+/// otherwise the crate's sources next to the `scripts` directory.
+pub fn package_path(package_dir: Option<&Path>, crate_name: &str) -> PathBuf {
+    match package_dir {
+        Some(dir) => dir.join(format!("{crate_name}.{PACKAGE_EXTENSION}")),
+        None => PathBuf::from("..").join(crate_name),
+    }
+}
+
+/// Loads `path` if it is a `.masp` file, otherwise compiles the Rust crate at `path`
+///
+/// This lets deployments run from prebuilt artifacts without the nightly toolchain or
+/// `cargo-miden` installed.
+pub fn load_or_compile_package(
+    path: impl AsRef<Path>,
+    options: &CompileOptions,
+) -> Result<Arc<Package>, DemoError> {
+    let path = path.as_ref();
+    if path.extension().is_some_and(|ext| ext == PACKAGE_EXTENSION) {
+        println!("  Loading prebuilt package: {}", path.display());
+        load_package(path)
+    } else {
+        compile_rust_package(&path.to_string_lossy(), options)
+    }
+}
+
+/// Like [`load_or_compile_package`], but fails unless the package is of the `expected` kind
+pub fn load_or_compile_package_of_kind(
+    path: impl AsRef<Path>,
+    expected: PackageKind,
+    options: &CompileOptions,
+) -> Result<Arc<Package>, DemoError> {
+    let path = path.as_ref();
+    let package = load_or_compile_package(path, options)?;
+    check_package_kind(path, &package, expected)?;
+    Ok(package)
+}
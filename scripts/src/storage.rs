@@ -0,0 +1,646 @@
+//! Named access to the storage of an account component
+//!
+//! Slot indices inside an account depend on which components come before a given component,
+//! e.g. the auth component takes slot 0. [`ComponentStorage`] resolves a slot by the name or
+//! description declared in the component's `AccountComponentMetadata`, and the component's
+//! offset from the account code, so callers never hand-compute slot indices. [`StorageInit`]
+//! goes the other way and builds a component's initial storage from named values.
+
+use std::{collections::BTreeMap, fs, path::Path};
+
+use miden_client::{
+    account::{Account, AccountStorage, StorageSlot},
+    Felt, Word,
+};
+use miden_mast_package::Package;
+use miden_objects::account::{
+    AccountComponent, AccountComponentMetadata, AccountComponentTemplate, InitStorageData,
+    StorageEntry, StorageValueName,
+};
+
+use crate::{
+    error::DemoError,
+    helpers::{component_metadata, package_library},
+};
+
+/// The storage of one component of an account
+pub struct ComponentStorage<'a> {
+    component: String,
+    storage: &'a AccountStorage,
+    metadata: AccountComponentMetadata,
+    offset: u8,
+}
+
+impl<'a> ComponentStorage<'a> {
+    /// Locates the component built from `package` in `account`
+    ///
+    /// The component's storage offset is taken from the account procedure that has the same
+    /// MAST root as one of the package's procedures.
+    pub fn new(account: &'a Account, package: &Package) -> Result<Self, DemoError> {
+        let library = package_library(package)?;
+        let metadata = component_metadata(package)?;
+
+        let roots: Vec<Word> = library.mast_forest().procedure_roots().to_vec();
+        let offset = account
+            .code()
+            .procedures()
+            .iter()
+            .find(|procedure| roots.contains(procedure.mast_root()))
+            .map(|procedure| procedure.storage_offset())
+            .ok_or_else(|| DemoError::ComponentNotInAccount(package.name.clone()))?;
+
+        Ok(Self {
+            component: package.name.clone(),
+            storage: account.storage(),
+            metadata,
+            offset,
+        })
+    }
+
+    /// Returns the account slot index of the storage entry called `name`
+    ///
+    /// `name` is matched against both the entry's name and its description, and has to match
+    /// exactly one entry.
+    pub fn slot_index(&self, name: &str) -> Result<u8, DemoError> {
+        let entry = find_entry(&self.component, &self.metadata, name)?;
+        Ok(self.offset + entry.slot_indices().start)
+    }
+
+    /// Returns the value in the slot called `name`
+    pub fn get_item(&self, name: &str) -> Result<Word, DemoError> {
+        Ok(self.storage.get_item(self.slot_index(name)?)?)
+    }
+
+    /// Returns the value at `key` in the map slot called `name`
+    pub fn get_map_item(&self, name: &str, key: Word) -> Result<Word, DemoError> {
+        Ok(self.storage.get_map_item(self.slot_index(name)?, key)?)
+    }
+
+    /// Asserts that the slot called `name` holds `expected`
+    pub fn assert_item(&self, name: &str, expected: Word) {
+        let actual = self
+            .get_item(name)
+            .unwrap_or_else(|err| panic!("Failed to read '{name}': {err}"));
+        assert_eq!(
+            actual, expected,
+            "Storage slot '{name}' of '{}' mismatch",
+            self.component
+        );
+    }
+
+    /// Asserts that the map slot called `name` holds `expected` at `key`
+    pub fn assert_map_item(&self, name: &str, key: Word, expected: Word) {
+        let actual = self
+            .get_map_item(name, key)
+            .unwrap_or_else(|err| panic!("Failed to read '{name}': {err}"));
+        assert_eq!(
+            actual, expected,
+            "Storage map '{name}' of '{}' mismatch at key {key:?}",
+            self.component
+        );
+    }
+}
+
+/// Whether `entry` is called `name`, by name or by description
+fn entry_matches(entry: &StorageEntry, name: &str) -> bool {
+    entry
+        .name()
+        .is_some_and(|entry_name| entry_name.as_str() == name)
+        || entry
+            .description()
+            .is_some_and(|description| description == name)
+}
+
+/// Returns the one storage entry of `component` called `name`, by name or by description
+fn find_entry<'m>(
+    component: &str,
+    metadata: &'m AccountComponentMetadata,
+    name: &str,
+) -> Result<&'m StorageEntry, DemoError> {
+    let mut matches = metadata
+        .storage_entries()
+        .iter()
+        .filter(|entry| entry_matches(entry, name));
+    let entry = matches
+        .next()
+        .ok_or_else(|| DemoError::UnknownStorageSlot {
+            component: component.to_string(),
+            name: name.to_string(),
+        })?;
+    if matches.next().is_some() {
+        return Err(DemoError::AmbiguousStorageSlot {
+            component: component.to_string(),
+            name: name.to_string(),
+        });
+    }
+    Ok(entry)
+}
+
+/// Initial value of one storage entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InitValue {
+    /// Value of a placeholder, in the form its type parses; words are hex
+    Value(String),
+    /// Entries added to a map slot
+    Map(Vec<(Word, Word)>),
+}
+
+/// Initial storage of a component, keyed by the slot names in its metadata
+///
+/// Values fill the placeholders the component's metadata declares, as upstream
+/// [`InitStorageData`] does; entries the metadata gives a value keep it. Map entries are added
+/// to the declared map slots.
+///
+/// In TOML, each top-level key names a storage entry or placeholder. A word, written as
+/// `"a,b,c,d"` or as an array of four integers, fills a word placeholder; other strings and
+/// integers are passed on as they are. A table of `"key" = "value"` words adds entries to a map
+/// slot:
+///
+/// ```toml
+/// "counter contract storage map" = { "0,0,0,1" = "0,0,0,1" }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct StorageInit {
+    entries: BTreeMap<String, InitValue>,
+}
+
+impl StorageInit {
+    /// Fills the word placeholder called `name`
+    pub fn with_value(mut self, name: impl Into<String>, value: Word) -> Self {
+        self.entries
+            .insert(name.into(), InitValue::Value(value.to_hex()));
+        self
+    }
+
+    /// Adds an entry to the map slot called `name`
+    pub fn with_map_entry(mut self, name: impl Into<String>, key: Word, value: Word) -> Self {
+        let entry = self
+            .entries
+            .entry(name.into())
+            .or_insert_with(|| InitValue::Map(vec![]));
+        match entry {
+            InitValue::Map(entries) => entries.push((key, value)),
+            InitValue::Value(_) => *entry = InitValue::Map(vec![(key, value)]),
+        }
+        self
+    }
+
+    /// Parses initial storage from TOML
+    pub fn from_toml(toml: &str) -> Result<Self, DemoError> {
+        let table: toml::Table =
+            toml::from_str(toml).map_err(|err| DemoError::InvalidStorageInit(err.to_string()))?;
+
+        let mut entries = BTreeMap::new();
+        for (name, value) in table {
+            let value = match value {
+                toml::Value::Table(map) => {
+                    let map = map
+                        .into_iter()
+                        .map(|(key, value)| Ok((parse_word(&key)?, toml_word(&value)?)))
+                        .collect::<Result<_, String>>()
+                        .map_err(|err| {
+                            DemoError::InvalidStorageInit(format!("map '{name}': {err}"))
+                        })?;
+                    InitValue::Map(map)
+                }
+                value => InitValue::Value(toml_placeholder(&value).map_err(|err| {
+                    DemoError::InvalidStorageInit(format!("value '{name}': {err}"))
+                })?),
+            };
+            entries.insert(name, value);
+        }
+        Ok(Self { entries })
+    }
+
+    /// Reads initial storage from a TOML file
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, DemoError> {
+        Self::from_toml(&fs::read_to_string(path)?)
+    }
+
+    /// Builds the component of `template` with this initial storage
+    ///
+    /// Values are passed to [`AccountComponent::from_template`], which fails if a placeholder
+    /// is left unfilled. Every initialized name has to be declared, values can only fill
+    /// placeholders, and maps can only initialize map slots.
+    pub fn build_component(
+        &self,
+        template: &AccountComponentTemplate,
+    ) -> Result<AccountComponent, DemoError> {
+        let metadata = template.metadata();
+        let component = metadata.name();
+        let placeholders = metadata.get_placeholder_requirements();
+
+        let mut values = BTreeMap::new();
+        let mut map_entries = vec![];
+        for (name, value) in &self.entries {
+            match value {
+                InitValue::Value(value) => {
+                    // Placeholders nested inside an entry, such as the felts of a word or the
+                    // keys of a map, are named `<entry>.<placeholder>`
+                    let (key, declared) = match find_entry(component, metadata, name) {
+                        Ok(StorageEntry::Map { .. }) => {
+                            return Err(DemoError::InvalidStorageInit(format!(
+                                "storage entry '{name}' is a map slot, but was initialized with \
+                                 a value"
+                            )))
+                        }
+                        Ok(entry) => (entry.name().cloned(), true),
+                        Err(DemoError::UnknownStorageSlot { .. }) => {
+                            (StorageValueName::new(name.as_str()).ok(), false)
+                        }
+                        Err(err) => return Err(err),
+                    };
+                    match key {
+                        Some(key) if placeholders.contains_key(&key) => {
+                            values.insert(key, value.clone());
+                        }
+                        _ if declared => {
+                            return Err(DemoError::InvalidStorageInit(format!(
+                                "storage entry '{name}' has a fixed value in the component's \
+                                 metadata"
+                            )))
+                        }
+                        _ => {
+                            return Err(DemoError::UnknownStorageSlot {
+                                component: component.to_string(),
+                                name: name.clone(),
+                            })
+                        }
+                    }
+                }
+                InitValue::Map(entries) => match find_entry(component, metadata, name)? {
+                    entry @ StorageEntry::Map { .. } => {
+                        map_entries.push((entry.slot_indices().start, entries))
+                    }
+                    _ => {
+                        return Err(DemoError::InvalidStorageInit(format!(
+                            "storage entry '{name}' is not a map slot, but was initialized with \
+                             a map"
+                        )))
+                    }
+                },
+            }
+        }
+
+        let built = AccountComponent::from_template(template, &InitStorageData::new(values))?;
+        if map_entries.is_empty() {
+            return Ok(built);
+        }
+        let mut slots = built.storage_slots().to_vec();
+        for (index, entries) in map_entries {
+            let StorageSlot::Map(map) = &mut slots[index as usize] else {
+                unreachable!("map entries are only added to map slots");
+            };
+            for (key, value) in entries {
+                map.insert((*key).into(), *value);
+            }
+        }
+        Ok(AccountComponent::new(template.library().clone(), slots)?
+            .with_supported_types(metadata.supported_types().clone()))
+    }
+}
+
+/// Checks that `slots` match the storage entries `metadata` declares, in number and kind
+pub fn check_storage_layout(
+    metadata: &AccountComponentMetadata,
+    slots: &[StorageSlot],
+) -> Result<(), DemoError> {
+    let layout_error = |message: String| DemoError::StorageLayout {
+        component: metadata.name().to_string(),
+        message,
+    };
+
+    let mut declared: Vec<&StorageEntry> = metadata.storage_entries().iter().collect();
+    declared.sort_by_key(|entry| entry.slot_indices().start);
+    let declared_len = declared
+        .last()
+        .map(|entry| entry.slot_indices().end as usize)
+        .unwrap_or(0);
+    if slots.len() != declared_len {
+        return Err(layout_error(format!(
+            "declares {declared_len} storage slots, got {}",
+            slots.len()
+        )));
+    }
+
+    for entry in declared {
+        for index in entry.slot_indices() {
+            let matches = matches!(
+                (entry, &slots[index as usize]),
+                (StorageEntry::Value { .. }, StorageSlot::Value(_))
+                    | (StorageEntry::MultiSlot { .. }, StorageSlot::Value(_))
+                    | (StorageEntry::Map { .. }, StorageSlot::Map(_))
+            );
+            if !matches {
+                return Err(layout_error(format!(
+                    "slot {index} does not match the declared kind"
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Parses a word given as four comma-separated felts
+pub fn parse_word(s: &str) -> Result<Word, String> {
+    let felts = s
+        .split(',')
+        .map(|felt| felt.trim().parse::<u64>().map(Felt::new))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| format!("invalid felt in '{s}': {err}"))?;
+    let felts: [Felt; 4] = felts
+        .try_into()
+        .map_err(|_| format!("expected 4 felts in '{s}'"))?;
+    Ok(Word::from(felts))
+}
+
+/// Formats a word as four comma-separated felts, the form [`parse_word`] reads
+pub fn format_word(word: Word) -> String {
+    word.iter()
+        .map(|felt| felt.as_int().to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Parses the value of a placeholder: words given as `"a,b,c,d"` or as an array of four
+/// integers become hex, other strings and integers are kept as they are
+fn toml_placeholder(value: &toml::Value) -> Result<String, String> {
+    match value {
+        toml::Value::String(s) => Ok(parse_word(s)
+            .map(|word| word.to_hex())
+            .unwrap_or_else(|_| s.clone())),
+        toml::Value::Integer(n) => Ok(n.to_string()),
+        toml::Value::Array(_) => toml_word(value).map(|word| word.to_hex()),
+        other => Err(format!("expected a word, felt or string, got {other}")),
+    }
+}
+
+/// Parses a word given as a `"a,b,c,d"` string or an array of four integers
+fn toml_word(value: &toml::Value) -> Result<Word, String> {
+    match value {
+        toml::Value::String(s) => parse_word(s),
+        toml::Value::Array(felts) => {
+            let felts = felts
+                .iter()
+                .map(|felt| {
+                    felt.as_integer()
+                        .and_then(|felt| u64::try_from(felt).ok())
+                        .map(Felt::new)
+                        .ok_or_else(|| format!("invalid felt {felt}"))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let felts: [Felt; 4] = felts
+                .try_into()
+                .map_err(|_| "expected 4 felts".to_string())?;
+            Ok(Word::from(felts))
+        }
+        other => Err(format!("expected a word, got {other}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_client::{
+        account::{StorageMap, StorageSlot},
+        Felt, Word,
+    };
+    use miden_lib::transaction::TransactionKernel;
+    use miden_objects::account::{
+        AccountComponent, AccountComponentMetadata, AccountComponentTemplate,
+    };
+
+    use super::{check_storage_layout, format_word, parse_word, toml_word, InitValue, StorageInit};
+    use crate::error::DemoError;
+
+    /// A value placeholder, a fixed value and a map
+    const METADATA: &str = r#"
+name = "test"
+description = "Component for the storage tests"
+version = "0.1.0"
+supported-types = ["RegularAccountUpdatableCode"]
+
+[[storage]]
+name = "owner"
+description = "owner of the component"
+slot = 0
+type = "word"
+
+[[storage]]
+name = "fixed"
+slot = 1
+value = ["1", "2", "3", "4"]
+
+[[storage]]
+name = "balances"
+description = "balances by key"
+slot = 2
+values = []
+"#;
+
+    fn metadata(toml: &str) -> AccountComponentMetadata {
+        AccountComponentMetadata::from_toml(toml).unwrap()
+    }
+
+    fn template(toml: &str) -> AccountComponentTemplate {
+        let library = AccountComponent::compile(
+            "export.noop push.0 drop end",
+            TransactionKernel::assembler(),
+            vec![],
+        )
+        .unwrap()
+        .library()
+        .clone();
+        AccountComponentTemplate::new(metadata(toml), library)
+    }
+
+    fn word(felts: [u64; 4]) -> Word {
+        Word::from(felts.map(Felt::new))
+    }
+
+    #[test]
+    fn words_parse_and_format() {
+        assert_eq!(parse_word("1, 2,3 ,4"), Ok(word([1, 2, 3, 4])));
+        assert_eq!(format_word(word([1, 2, 3, 4])), "1,2,3,4");
+        assert_eq!(
+            parse_word(&format_word(word([5, 0, 7, 9]))),
+            Ok(word([5, 0, 7, 9]))
+        );
+        assert!(parse_word("1,2,3").is_err());
+        assert!(parse_word("1,2,3,4,5").is_err());
+        assert!(parse_word("1,2,x,4").is_err());
+    }
+
+    #[test]
+    fn toml_words_are_strings_or_arrays() {
+        let value: toml::Table = toml::from_str(r#"a = "1,2,3,4""#).unwrap();
+        assert_eq!(toml_word(&value["a"]), Ok(word([1, 2, 3, 4])));
+        let value: toml::Table = toml::from_str("a = [1, 2, 3, 4]").unwrap();
+        assert_eq!(toml_word(&value["a"]), Ok(word([1, 2, 3, 4])));
+
+        for invalid in ["a = [1, 2, 3]", "a = [1, 2, 3, -4]", "a = true"] {
+            let value: toml::Table = toml::from_str(invalid).unwrap();
+            assert!(toml_word(&value["a"]).is_err(), "{invalid} parsed");
+        }
+    }
+
+    #[test]
+    fn from_toml_reads_values_and_maps() {
+        let init = StorageInit::from_toml(
+            r#"
+owner = "1,2,3,4"
+decimals = 8
+symbol = "TST"
+"balances by key" = { "0,0,0,1" = [0, 0, 0, 5], "0,0,0,2" = "0,0,0,6" }
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            init.entries["owner"],
+            InitValue::Value(word([1, 2, 3, 4]).to_hex())
+        );
+        assert_eq!(init.entries["decimals"], InitValue::Value("8".to_string()));
+        assert_eq!(init.entries["symbol"], InitValue::Value("TST".to_string()));
+        assert_eq!(
+            init.entries["balances by key"],
+            InitValue::Map(vec![
+                (word([0, 0, 0, 1]), word([0, 0, 0, 5])),
+                (word([0, 0, 0, 2]), word([0, 0, 0, 6])),
+            ])
+        );
+    }
+
+    #[test]
+    fn from_toml_rejects_malformed_maps() {
+        for invalid in [
+            r#"m = { "1,2" = "0,0,0,1" }"#,
+            r#"m = { "0,0,0,1" = "0,0,1" }"#,
+            "m = true",
+            "not toml",
+        ] {
+            assert!(
+                matches!(
+                    StorageInit::from_toml(invalid),
+                    Err(DemoError::InvalidStorageInit(_))
+                ),
+                "{invalid} parsed"
+            );
+        }
+    }
+
+    #[test]
+    fn placeholders_are_filled_and_defaults_kept() {
+        let template = template(METADATA);
+        let owner = word([1, 2, 3, 4]);
+        let (key, value) = (word([0, 0, 0, 1]), word([0, 0, 0, 9]));
+
+        let component = StorageInit::default()
+            .with_value("owner", owner)
+            .with_map_entry("balances by key", key, value)
+            .build_component(&template)
+            .unwrap();
+        let slots = component.storage_slots();
+
+        assert_eq!(slots[0], StorageSlot::Value(owner));
+        assert_eq!(slots[1], StorageSlot::Value(word([1, 2, 3, 4])));
+        assert_eq!(
+            slots[2],
+            StorageSlot::Map(StorageMap::with_entries([(key.into(), value)]).unwrap())
+        );
+    }
+
+    #[test]
+    fn unfilled_placeholders_are_rejected() {
+        assert!(StorageInit::default()
+            .build_component(&template(METADATA))
+            .is_err());
+    }
+
+    #[test]
+    fn fixed_values_cannot_be_overridden() {
+        let result = StorageInit::default()
+            .with_value("owner", word([1, 2, 3, 4]))
+            .with_value("fixed", word([0, 0, 0, 0]))
+            .build_component(&template(METADATA));
+        assert!(matches!(result, Err(DemoError::InvalidStorageInit(_))));
+    }
+
+    #[test]
+    fn unknown_names_are_rejected() {
+        let result = StorageInit::default()
+            .with_value("owner", word([1, 2, 3, 4]))
+            .with_value("nobody", word([0, 0, 0, 0]))
+            .build_component(&template(METADATA));
+        assert!(
+            matches!(result, Err(DemoError::UnknownStorageSlot { name, .. }) if name == "nobody")
+        );
+    }
+
+    #[test]
+    fn kind_mismatches_are_rejected() {
+        let map_as_value = StorageInit::default()
+            .with_value("owner", word([1, 2, 3, 4]))
+            .with_value("balances", word([0, 0, 0, 1]))
+            .build_component(&template(METADATA));
+        assert!(matches!(
+            map_as_value,
+            Err(DemoError::InvalidStorageInit(_))
+        ));
+
+        let value_as_map = StorageInit::default()
+            .with_map_entry("owner", word([0, 0, 0, 1]), word([0, 0, 0, 1]))
+            .build_component(&template(METADATA));
+        assert!(matches!(
+            value_as_map,
+            Err(DemoError::InvalidStorageInit(_))
+        ));
+    }
+
+    #[test]
+    fn ambiguous_names_are_rejected() {
+        // `first` is the description of one entry and the name of the other
+        let metadata = r#"
+name = "ambiguous"
+description = "Two entries called the same"
+version = "0.1.0"
+supported-types = ["RegularAccountUpdatableCode"]
+
+[[storage]]
+name = "map"
+description = "first"
+slot = 0
+values = []
+
+[[storage]]
+name = "first"
+slot = 1
+values = []
+"#;
+        let result = StorageInit::default()
+            .with_map_entry("first", word([0, 0, 0, 1]), word([0, 0, 0, 1]))
+            .build_component(&template(metadata));
+        assert!(matches!(
+            result,
+            Err(DemoError::AmbiguousStorageSlot { name, .. }) if name == "first"
+        ));
+    }
+
+    #[test]
+    fn layout_checks_count_and_kind() {
+        let metadata = metadata(METADATA);
+        let value = StorageSlot::Value(word([0, 0, 0, 0]));
+        let map = StorageSlot::Map(StorageMap::new());
+
+        assert!(
+            check_storage_layout(&metadata, &[value.clone(), value.clone(), map.clone()]).is_ok()
+        );
+        assert!(matches!(
+            check_storage_layout(&metadata, &[value.clone(), value.clone()]),
+            Err(DemoError::StorageLayout { .. })
+        ));
+        assert!(matches!(
+            check_storage_layout(&metadata, &[value.clone(), map.clone(), map]),
+            Err(DemoError::StorageLayout { .. })
+        ));
+    }
+}
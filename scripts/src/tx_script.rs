@@ -0,0 +1,301 @@
+//! Host-side encoding of the advice input read by `basic-wallet-tx-script`
+//!
+//! The script reads its input from the advice map, under the commitment passed as its argument.
+//! The layout is versioned and mirrors the constants at the top of
+//! `basic-wallet-tx-script/src/lib.rs`; both sides have to change together.
+//!
+//! ```text
+//! header: [version, num_notes, 0, 0]
+//! note:   [tag, aux, note_type, execution_hint, RECIPIENT, num_assets, 0, 0, 0, ASSET * num_assets]
+//! ```
+
+use miden_client::{
+    note::{NoteExecutionHint, NoteRecipient, NoteTag, NoteType},
+    transaction::TransactionRequestBuilder,
+    Felt, Word,
+};
+use miden_core::crypto::hash::Rpo256;
+use miden_objects::{asset::Asset, FieldElement};
+
+/// Version of the input layout this crate encodes
+pub const WALLET_INPUT_VERSION: u64 = 1;
+
+/// Advice input of a transaction script, stored in the advice map under its commitment
+///
+/// Values are appended a word at a time, with shorter groups padded with zeros, so the input
+/// is always word-aligned as `adv_load_preimage` requires. The script receives the commitment
+/// as its argument and loads the input with it.
+#[derive(Debug, Clone, Default)]
+pub struct TxScriptInput {
+    felts: Vec<Felt>,
+}
+
+impl TxScriptInput {
+    /// An empty input
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a word
+    pub fn with_word(mut self, word: impl Into<[Felt; 4]>) -> Self {
+        self.felts.extend(word.into());
+        self
+    }
+
+    /// Appends `felts`, padded with zeros to the next word boundary
+    pub fn with_padded(mut self, felts: &[Felt]) -> Self {
+        self.felts.extend_from_slice(felts);
+        let padding = (4 - felts.len() % 4) % 4;
+        self.felts.extend(std::iter::repeat_n(Felt::ZERO, padding));
+        self
+    }
+
+    /// Appends a `u64` count or length, padded to a word
+    pub fn with_count(self, count: usize) -> Self {
+        self.with_padded(&[Felt::new(count as u64)])
+    }
+
+    /// Returns the encoded felts
+    pub fn felts(&self) -> &[Felt] {
+        &self.felts
+    }
+
+    /// Returns the hash the input is stored under in the advice map
+    pub fn commitment(&self) -> Word {
+        Rpo256::hash_elements(&self.felts).into()
+    }
+
+    /// Returns the script argument: the commitment in the order the script reads it from the
+    /// stack, i.e. reversed
+    pub fn script_arg(&self) -> [Felt; 4] {
+        let mut arg: [Felt; 4] = self.commitment().into();
+        arg.reverse();
+        arg
+    }
+
+    /// Adds the script argument and the advice map entry to a transaction request
+    pub fn apply(self, builder: TransactionRequestBuilder) -> TransactionRequestBuilder {
+        let script_arg = self.script_arg();
+        builder
+            .script_arg(script_arg)
+            .extend_advice_map([(self.commitment().into(), self.felts)])
+    }
+}
+
+/// One note the basic wallet transaction script creates
+#[derive(Debug, Clone)]
+pub struct WalletNote {
+    pub tag: NoteTag,
+    pub aux: Felt,
+    pub note_type: NoteType,
+    pub execution_hint: NoteExecutionHint,
+    pub recipient: NoteRecipient,
+    /// Assets moved from the sender's vault into the note
+    pub assets: Vec<Asset>,
+}
+
+/// Encodes `notes` in the layout `basic-wallet-tx-script` reads
+pub fn encode_wallet_input(notes: &[WalletNote]) -> TxScriptInput {
+    let mut input = TxScriptInput::new().with_padded(&[
+        Felt::new(WALLET_INPUT_VERSION),
+        Felt::new(notes.len() as u64),
+    ]);
+    for note in notes {
+        input = input
+            .with_padded(&[
+                note.tag.into(),
+                note.aux,
+                note.note_type.into(),
+                note.execution_hint.into(),
+            ])
+            .with_word(note.recipient.digest())
+            .with_count(note.assets.len());
+        for asset in &note.assets {
+            input = input.with_word(*asset);
+        }
+    }
+    input
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_client::{
+        account::{component::BasicFungibleFaucet, AccountBuilder, AccountType},
+        asset::{Asset, FungibleAsset, TokenSymbol},
+        note::{NoteExecutionHint, NoteInputs, NoteRecipient, NoteScript, NoteTag, NoteType},
+        Felt, Word,
+    };
+    use miden_lib::{account::auth::NoAuth, transaction::TransactionKernel};
+    use miden_objects::FieldElement;
+
+    use miden_core::crypto::hash::Rpo256;
+
+    use super::{encode_wallet_input, TxScriptInput, WalletNote, WALLET_INPUT_VERSION};
+
+    /// Assets of a fungible faucet built for the tests
+    fn fungible_assets(amounts: &[u64]) -> Vec<Asset> {
+        let (faucet, _) = AccountBuilder::new([7; 32])
+            .account_type(AccountType::FungibleFaucet)
+            .with_auth_component(NoAuth)
+            .with_component(
+                BasicFungibleFaucet::new(TokenSymbol::new("TST").unwrap(), 8, Felt::new(1_000))
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        amounts
+            .iter()
+            .map(|&amount| FungibleAsset::new(faucet.id(), amount).unwrap().into())
+            .collect()
+    }
+
+    fn wallet_note(serial: u64, assets: Vec<Asset>) -> WalletNote {
+        let script =
+            NoteScript::compile("begin push.1 drop end", TransactionKernel::assembler()).unwrap();
+        WalletNote {
+            tag: NoteTag::for_local_use_case(3, 0).unwrap(),
+            aux: Felt::new(serial + 100),
+            note_type: NoteType::Private,
+            execution_hint: NoteExecutionHint::always(),
+            recipient: NoteRecipient::new(
+                Word::from([Felt::new(serial); 4]),
+                script,
+                NoteInputs::new(vec![]).unwrap(),
+            ),
+            assets,
+        }
+    }
+
+    /// The felts `note` is encoded as, written out by hand
+    fn expected_note(note: &WalletNote) -> Vec<Felt> {
+        let mut felts = vec![
+            note.tag.into(),
+            note.aux,
+            note.note_type.into(),
+            note.execution_hint.into(),
+        ];
+        felts.extend(<[Felt; 4]>::from(note.recipient.digest()));
+        felts.extend([
+            Felt::new(note.assets.len() as u64),
+            Felt::ZERO,
+            Felt::ZERO,
+            Felt::ZERO,
+        ]);
+        for asset in &note.assets {
+            felts.extend(<[Felt; 4]>::from(Word::from(*asset)));
+        }
+        felts
+    }
+
+    fn header(num_notes: u64) -> Vec<Felt> {
+        vec![
+            Felt::new(WALLET_INPUT_VERSION),
+            Felt::new(num_notes),
+            Felt::ZERO,
+            Felt::ZERO,
+        ]
+    }
+
+    #[test]
+    fn no_notes_encode_to_the_header() {
+        assert_eq!(encode_wallet_input(&[]).felts(), header(0));
+    }
+
+    #[test]
+    fn one_note_without_assets() {
+        let note = wallet_note(1, vec![]);
+        let input = encode_wallet_input(std::slice::from_ref(&note));
+
+        assert_eq!(input.felts().len(), 4 + 12);
+        assert_eq!(input.felts(), [header(1), expected_note(&note)].concat());
+    }
+
+    #[test]
+    fn one_note_with_one_asset() {
+        let note = wallet_note(1, fungible_assets(&[5]));
+        let input = encode_wallet_input(std::slice::from_ref(&note));
+
+        assert_eq!(input.felts().len(), 4 + 12 + 4);
+        assert_eq!(input.felts(), [header(1), expected_note(&note)].concat());
+    }
+
+    #[test]
+    fn several_notes_and_assets_follow_each_other() {
+        let notes = vec![
+            wallet_note(1, fungible_assets(&[5, 6])),
+            wallet_note(2, vec![]),
+            wallet_note(3, fungible_assets(&[7])),
+        ];
+        let input = encode_wallet_input(&notes);
+
+        let expected: Vec<Felt> = header(3)
+            .into_iter()
+            .chain(notes.iter().flat_map(expected_note))
+            .collect();
+        assert_eq!(input.felts().len(), 4 + (12 + 8) + 12 + (12 + 4));
+        assert_eq!(input.felts(), expected);
+
+        // The second note starts right after the first note's assets
+        let second = &input.felts()[4 + 12 + 8..];
+        assert_eq!(second[1], Felt::new(102));
+        assert_eq!(second[8], Felt::ZERO);
+    }
+
+    fn felts(values: &[u64]) -> Vec<Felt> {
+        values.iter().copied().map(Felt::new).collect()
+    }
+
+    #[test]
+    fn padded_groups_end_on_a_word_boundary() {
+        for len in 0..=9u64 {
+            let values: Vec<u64> = (1..=len).collect();
+            let input = TxScriptInput::new().with_padded(&felts(&values));
+
+            let padded_len = len.div_ceil(4) * 4;
+            let mut expected = values.clone();
+            expected.resize(padded_len as usize, 0);
+            assert_eq!(input.felts(), felts(&expected), "{len} felts");
+        }
+    }
+
+    #[test]
+    fn padding_does_not_carry_over_between_groups() {
+        let input = TxScriptInput::new()
+            .with_padded(&felts(&[1]))
+            .with_word(Word::from([Felt::new(2); 4]))
+            .with_count(3)
+            .with_padded(&felts(&[4, 5, 6, 7, 8]));
+        assert_eq!(
+            input.felts(),
+            felts(&[1, 0, 0, 0, 2, 2, 2, 2, 3, 0, 0, 0, 4, 5, 6, 7, 8, 0, 0, 0])
+        );
+    }
+
+    #[test]
+    fn commitment_is_the_hash_of_the_felts() {
+        let input = encode_wallet_input(&[wallet_note(1, fungible_assets(&[5]))]);
+        assert_eq!(
+            input.commitment(),
+            Word::from(Rpo256::hash_elements(input.felts()))
+        );
+
+        let empty = TxScriptInput::new();
+        assert_eq!(
+            empty.commitment(),
+            Word::from(Rpo256::hash_elements::<Felt>(&[]))
+        );
+        assert_ne!(input.commitment(), empty.commitment());
+    }
+
+    #[test]
+    fn script_arg_is_the_reversed_commitment() {
+        let input = TxScriptInput::new().with_padded(&felts(&[1, 2, 3]));
+        let commitment: [Felt; 4] = input.commitment().into();
+        let arg = input.script_arg();
+
+        assert_eq!(
+            arg,
+            [commitment[3], commitment[2], commitment[1], commitment[0]]
+        );
+    }
+}
@@ -0,0 +1,40 @@
+//! Building accounts from several component packages
+
+use miden_client::account::AccountType;
+use miden_demo::{
+    build_client, create_account_with_components, storage::StorageInit, AccountCreationConfig,
+    ClientSettings, DemoError, Network,
+};
+
+#[tokio::test]
+async fn component_settings_on_the_account_config_are_rejected() {
+    let keystore = tempfile::tempdir().unwrap();
+    let mut settings = ClientSettings::new(Network::Mock);
+    settings.keystore = keystore.path().to_path_buf();
+    let (mut client, keystore) = build_client(&settings).await.unwrap();
+
+    let configs = [
+        (
+            "storage_init",
+            AccountCreationConfig {
+                storage_init: Some(StorageInit::default()),
+                ..Default::default()
+            },
+        ),
+        (
+            "supported_types",
+            AccountCreationConfig {
+                supported_types: Some(vec![AccountType::RegularAccountUpdatableCode]),
+                ..Default::default()
+            },
+        ),
+    ];
+    for (field, config) in configs {
+        let result =
+            create_account_with_components(&mut client, keystore.clone(), vec![], config).await;
+        assert!(
+            matches!(result, Err(DemoError::ComponentSettingOnAccountConfig(name)) if name == field),
+            "{field} was accepted"
+        );
+    }
+}
@@ -0,0 +1,96 @@
+//! Counter contract flow across account types and storage modes, on the mock chain
+
+use std::sync::{Arc, OnceLock};
+
+use miden_client::account::{AccountStorageMode, AccountType};
+use miden_demo::{
+    build_client, compile_rust_packages,
+    counter::{counter_value, deploy_and_increment_counter},
+    AccountCreationConfig, ClientSettings, CompileOptions, Network, PackageKind,
+};
+use miden_mast_package::Package;
+
+/// Compiles the counter contract and note packages once for all cases
+fn counter_packages() -> (Arc<Package>, Arc<Package>) {
+    static PACKAGES: OnceLock<(Arc<Package>, Arc<Package>)> = OnceLock::new();
+    PACKAGES
+        .get_or_init(|| {
+            let packages = compile_rust_packages(
+                &["../counter-contract", "../counter-contract-note"],
+                &CompileOptions::default(),
+            )
+            .unwrap();
+            (
+                packages[0].package_of_kind(PackageKind::Account).unwrap(),
+                packages[1]
+                    .package_of_kind(PackageKind::NoteScript)
+                    .unwrap(),
+            )
+        })
+        .clone()
+}
+
+/// Deploys a counter account of `account_type` and `storage_mode` on a fresh mock chain and
+/// checks that consuming a counter note increments it
+async fn increments_counter(account_type: AccountType, storage_mode: AccountStorageMode) {
+    let (contract_package, note_package) = counter_packages();
+    let keystore = tempfile::tempdir().unwrap();
+    let mut settings = ClientSettings::new(Network::Mock);
+    settings.keystore = keystore.path().to_path_buf();
+    let (mut client, keystore) = build_client(&settings).await.unwrap();
+
+    let config = AccountCreationConfig {
+        account_type,
+        storage_mode,
+        ..Default::default()
+    };
+    let account = deploy_and_increment_counter(
+        &mut client,
+        keystore,
+        contract_package.clone(),
+        note_package,
+        config,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(account.id().account_type(), account_type);
+    assert_eq!(account.id().storage_mode(), storage_mode);
+    assert_eq!(counter_value(&account, &contract_package).unwrap(), 2);
+}
+
+#[tokio::test]
+async fn updatable_public() {
+    increments_counter(
+        AccountType::RegularAccountUpdatableCode,
+        AccountStorageMode::Public,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn updatable_private() {
+    increments_counter(
+        AccountType::RegularAccountUpdatableCode,
+        AccountStorageMode::Private,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn immutable_public() {
+    increments_counter(
+        AccountType::RegularAccountImmutableCode,
+        AccountStorageMode::Public,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn immutable_private() {
+    increments_counter(
+        AccountType::RegularAccountImmutableCode,
+        AccountStorageMode::Private,
+    )
+    .await;
+}
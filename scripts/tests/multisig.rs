@@ -0,0 +1,92 @@
+//! Transactions against accounts with a multisig auth component, on the mock chain
+
+use std::sync::Arc;
+
+use miden_client::{account::Account, asset::TokenSymbol, Client, Felt};
+use miden_demo::{
+    build_client, compile_rust_packages, create_account_with_components,
+    faucet::{create_fungible_faucet_account, mint_to_recipients},
+    AccountCreationConfig, AuthScheme, ClientSettings, CompileOptions, Network, PackageKind,
+};
+use miden_mast_package::Package;
+use tempfile::TempDir;
+
+/// A mock client with a plaintext keystore, a 2-of-2 multisig faucet and a wallet to mint to
+async fn multisig_faucet() -> (Client, TempDir, Account, Account) {
+    let keystore_dir = tempfile::tempdir().unwrap();
+    let mut settings = ClientSettings::new(Network::Mock);
+    settings.keystore = keystore_dir.path().to_path_buf();
+    let (mut client, keystore) = build_client(&settings).await.unwrap();
+
+    let faucet = create_fungible_faucet_account(
+        &mut client,
+        keystore.clone(),
+        TokenSymbol::new("MSIG").unwrap(),
+        8,
+        Felt::new(1_000_000),
+        AuthScheme::Multisig { signers: 2 },
+    )
+    .await
+    .unwrap();
+    let wallet = create_account_with_components(
+        &mut client,
+        keystore,
+        vec![],
+        AccountCreationConfig {
+            auth: AuthScheme::NoAuth,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    (client, keystore_dir, faucet, wallet)
+}
+
+/// Compiles the P2ID note the faucet mints with
+fn p2id_package() -> Arc<Package> {
+    compile_rust_packages(&["../p2id-note"], &CompileOptions::default()).unwrap()[0]
+        .package_of_kind(PackageKind::NoteScript)
+        .unwrap()
+}
+
+#[tokio::test]
+async fn both_signers_authorize_a_mint() {
+    let (mut client, _keystore, faucet, wallet) = multisig_faucet().await;
+    let public_keys = AuthScheme::Multisig { signers: 2 }
+        .public_keys(&faucet)
+        .unwrap();
+    assert_eq!(public_keys.len(), 2);
+    assert_ne!(public_keys[0], public_keys[1]);
+
+    let (_, notes) = mint_to_recipients(
+        &mut client,
+        faucet.id(),
+        p2id_package(),
+        &[(wallet.id(), 100)],
+    )
+    .await
+    .unwrap();
+    assert_eq!(notes.len(), 1);
+}
+
+#[tokio::test]
+async fn a_missing_signer_key_fails_the_transaction() {
+    let (mut client, keystore, faucet, wallet) = multisig_faucet().await;
+
+    // The wallet has no keys, so the keystore holds exactly the faucet's two signer keys
+    let key_files: Vec<_> = std::fs::read_dir(keystore.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    assert_eq!(key_files.len(), 2);
+    std::fs::remove_file(&key_files[0]).unwrap();
+
+    let result = mint_to_recipients(
+        &mut client,
+        faucet.id(),
+        p2id_package(),
+        &[(wallet.id(), 100)],
+    )
+    .await;
+    assert!(result.is_err(), "minted with one of two signer keys");
+}
@@ -0,0 +1,49 @@
+//! Reproducibility of seeded clients
+
+use miden_client::{account::AccountId, asset::TokenSymbol, crypto::FeltRng, Felt, Word};
+use miden_demo::{
+    build_client, faucet::create_fungible_faucet_account, AuthScheme, ClientSettings, DemoError,
+    Network,
+};
+
+fn seeded_settings(network: Network, keystore: &tempfile::TempDir) -> ClientSettings {
+    let mut settings = ClientSettings::new(network);
+    settings.keystore = keystore.path().to_path_buf();
+    settings.seed = Some(42);
+    settings
+}
+
+/// Creates a faucet and draws a note serial number with a fresh mock client seeded with 42
+async fn seeded_mock_run() -> (AccountId, Word) {
+    let keystore = tempfile::tempdir().unwrap();
+    let settings = seeded_settings(Network::Mock, &keystore);
+    let (mut client, keystore) = build_client(&settings).await.unwrap();
+
+    let faucet = create_fungible_faucet_account(
+        &mut client,
+        keystore,
+        TokenSymbol::new("SEED").unwrap(),
+        8,
+        Felt::new(1_000_000),
+        AuthScheme::default(),
+    )
+    .await
+    .unwrap();
+    (faucet.id(), client.rng().draw_word())
+}
+
+#[tokio::test]
+async fn seeded_mock_runs_are_identical() {
+    assert_eq!(seeded_mock_run().await, seeded_mock_run().await);
+}
+
+#[tokio::test]
+async fn seed_is_rejected_for_an_existing_store() {
+    let keystore = tempfile::tempdir().unwrap();
+    let store = tempfile::NamedTempFile::new().unwrap();
+    let mut settings = seeded_settings(Network::Local, &keystore);
+    settings.store = Some(store.path().to_path_buf());
+
+    let result = build_client(&settings).await;
+    assert!(matches!(result, Err(DemoError::SeededStoreNotFresh(path)) if path == store.path()));
+}